@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use color_eyre::eyre::WrapErr as _;
+use serde::{Deserialize, Serialize};
+
+use crate::shared::{Part, PartSolution};
+
+/// Known answers, keyed by input name, then by day (`day_01`), then by part (`part_1`, or `part_torus` for a bonus
+/// part). The answers are written the way [`PartSolution`] is serialized everywhere else, with their type.
+#[derive(Default, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, BTreeMap<String, BTreeMap<String, PartSolution>>>);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    Pass,
+    Fail {
+        expected: PartSolution,
+    },
+    Missing,
+    /// The part didn't produce an answer to verify.
+    Error,
+}
+
+impl std::fmt::Display for Verification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Verification::Pass => write!(f, "pass"),
+            Verification::Fail { .. } => write!(f, "FAIL"),
+            Verification::Missing => write!(f, "missing"),
+            Verification::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+pub fn day_key(day: u32) -> String {
+    format!("day_{:02}", day)
+}
+
+pub fn part_key(part: &Part) -> String {
+    format!("part_{}", part)
+}
+
+/// `name` as a TOML key, quoted unless it can be written bare.
+fn toml_key(name: &str) -> String {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        name.to_owned()
+    } else {
+        toml::Value::String(name.to_owned()).to_string()
+    }
+}
+
+impl Answers {
+    /// Loads the answers file at `path`, a file that doesn't exist yet has no answers.
+    pub fn load(path: &Path) -> Result<Self, color_eyre::Report> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read answers from {}", path.display()))?;
+
+        toml::from_str(&contents)
+            .wrap_err_with(|| format!("Failed to parse answers in {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), color_eyre::Report> {
+        let contents = self.to_toml().wrap_err("Failed to serialize answers")?;
+
+        std::fs::write(path, contents)
+            .wrap_err_with(|| format!("Failed to write answers to {}", path.display()))
+    }
+
+    /// The answers as TOML, with a table per input and day and every answer on a line of its own.
+    fn to_toml(&self) -> Result<String, color_eyre::Report> {
+        let mut contents = String::new();
+
+        for (input, days) in &self.0 {
+            for (day, parts) in days {
+                if !contents.is_empty() {
+                    contents.push('\n');
+                }
+
+                writeln!(contents, "[{}.{}]", toml_key(input), toml_key(day))?;
+
+                for (part, answer) in parts {
+                    writeln!(
+                        contents,
+                        "{} = {}",
+                        toml_key(part),
+                        toml::Value::try_from(answer)?
+                    )?;
+                }
+            }
+        }
+
+        Ok(contents)
+    }
+
+    pub fn get(&self, input: &str, day: u32, part: &Part) -> Option<&PartSolution> {
+        self.0.get(input)?.get(&day_key(day))?.get(&part_key(part))
+    }
+
+    pub fn insert(&mut self, input: &str, day: u32, part: &Part, answer: PartSolution) {
+        self.0
+            .entry(input.to_owned())
+            .or_default()
+            .entry(day_key(day))
+            .or_default()
+            .insert(part_key(part), answer);
+    }
+
+    pub fn verify(
+        &self,
+        input: &str,
+        day: u32,
+        part: &Part,
+        answer: &PartSolution,
+    ) -> Verification {
+        match self.get(input, day, part) {
+            None => Verification::Missing,
+            Some(expected) if answer.matches(expected) => Verification::Pass,
+            Some(expected) => Verification::Fail {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::answers::{Answers, Verification};
+    use crate::shared::{Part, PartSolution};
+
+    #[test]
+    fn verify() {
+        let answers: Answers = toml::from_str(
+            r#"
+            [input.day_01]
+            part_1 = { type = "U32", value = 1019571 }
+
+            [example.day_01]
+            part_1 = { type = "U32", value = 514579 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers.verify("input", 1, &Part::Puzzle(1), &PartSolution::I32(1_019_571)),
+            Verification::Pass
+        );
+        assert_eq!(
+            answers.verify(
+                "example",
+                1,
+                &Part::Puzzle(1),
+                &PartSolution::I32(1_019_571)
+            ),
+            Verification::Fail {
+                expected: PartSolution::U32(514_579)
+            }
+        );
+        assert_eq!(
+            answers.verify("input", 1, &Part::Puzzle(2), &PartSolution::I32(1)),
+            Verification::Missing
+        );
+        assert_eq!(
+            answers.verify("input", 2, &Part::Puzzle(1), &PartSolution::I32(1)),
+            Verification::Missing
+        );
+    }
+
+    #[test]
+    fn answers_are_verified_by_type() {
+        let answers: Answers = toml::from_str(
+            r#"
+            [input.day_13]
+            part_1 = { type = "USize", value = 119 }
+            part_2 = { type = "U128", value = 1106724616194525 }
+            part_code = { type = "String", value = "0119" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers.verify("input", 13, &Part::Puzzle(1), &PartSolution::U32(119)),
+            Verification::Pass
+        );
+        assert_eq!(
+            answers.verify(
+                "input",
+                13,
+                &Part::Puzzle(2),
+                &PartSolution::U64(1_106_724_616_194_525)
+            ),
+            Verification::Pass
+        );
+        assert_eq!(
+            answers.verify(
+                "input",
+                13,
+                &Part::Puzzle(1),
+                &PartSolution::String("119".to_owned())
+            ),
+            Verification::Fail {
+                expected: PartSolution::USize(119)
+            }
+        );
+        assert_eq!(
+            answers.verify(
+                "input",
+                13,
+                &Part::Bonus("code".to_owned()),
+                &PartSolution::String("0119".to_owned())
+            ),
+            Verification::Pass
+        );
+    }
+
+    #[test]
+    fn insert_round_trips() {
+        let mut answers = Answers::default();
+
+        answers.insert("input", 7, &Part::Puzzle(2), PartSolution::U32(172_246));
+        answers.insert(
+            "input",
+            7,
+            &Part::Bonus("striped".to_owned()),
+            PartSolution::Vec(vec!["#.".to_owned(), ".#".to_owned()]),
+        );
+
+        answers.insert(
+            "my example",
+            7,
+            &Part::Puzzle(1),
+            PartSolution::String("shiny gold".to_owned()),
+        );
+
+        let serialized = answers.to_toml().unwrap();
+
+        assert_eq!(
+            serialized,
+            r##"[input.day_07]
+part_2 = { type = "U32", value = 172246 }
+part_striped = { type = "Vec", value = ["#.", ".#"] }
+
+["my example".day_07]
+part_1 = { type = "String", value = "shiny gold" }
+"##
+        );
+        assert_eq!(toml::from_str::<Answers>(&serialized).unwrap(), answers);
+    }
+}
@@ -0,0 +1,161 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::WrapErr as _;
+
+use crate::cli::BenchArgs;
+use crate::registry::Registry;
+use crate::runner::{self, Job, Outcome};
+use crate::table::render_table;
+use crate::timing::{Stats, format_duration, time};
+
+pub fn bench(solutions: &Registry, args: &BenchArgs) -> Result<(), color_eyre::Report> {
+    let (rows, wall_time) = time(|| -> Result<Vec<[String; 8]>, color_eyre::Report> {
+        let jobs = runner::jobs(solutions, &args.selection)?;
+
+        let mut rows = Vec::with_capacity(jobs.len());
+
+        runner::schedule(
+            &jobs,
+            args.selection.jobs,
+            |job| bench_job(job, args.iterations),
+            |row| rows.push(row),
+        );
+
+        Ok(rows)
+    });
+
+    let report = format!(
+        "{}\nTotal wall time: {}\n",
+        render_table(
+            &[
+                "Day",
+                "Part",
+                "Iterations",
+                "Parse",
+                "Min",
+                "Median",
+                "Mean",
+                "Stddev"
+            ],
+            &rows?,
+        ),
+        format_duration(wall_time)
+    );
+
+    print!("{}", report);
+
+    if let Some(ref dir) = args.save {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let path = dir.join(format!("bench-{}.txt", timestamp));
+
+        std::fs::write(&path, report)
+            .wrap_err_with(|| format!("Failed to write benchmark to {}", path.display()))?;
+
+        println!("Saved benchmark to {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Parses and solves the part `iterations` times, the parse and solve times are reported separately. A part that
+/// doesn't answer is reported as failed after the first iteration, without timings.
+fn bench_job(job: &Job<'_>, iterations: u32) -> [String; 8] {
+    let mut parse_samples = Vec::new();
+    let mut solve_samples = Vec::new();
+
+    for _ in 0..iterations {
+        let (parsed, parse) = job.parse();
+
+        let outcome = match parsed {
+            Ok(parsed) => {
+                let (outcome, solve) = job.solve_parsed(&parsed);
+
+                solve_samples.push(solve);
+
+                outcome
+            },
+            Err(outcome) => outcome,
+        };
+
+        parse_samples.push(parse);
+
+        if let Outcome::Failed(_) | Outcome::Panicked(_) | Outcome::TimedOut(_) = outcome {
+            return [
+                job.day.to_string(),
+                job.part.to_string(),
+                iterations.to_string(),
+                "-".to_owned(),
+                outcome.describe(),
+                "-".to_owned(),
+                "-".to_owned(),
+                "-".to_owned(),
+            ];
+        }
+    }
+
+    let parse = Stats::new(&parse_samples).expect("At least one iteration");
+    let stats = Stats::new(&solve_samples).expect("At least one iteration");
+
+    [
+        job.day.to_string(),
+        job.part.to_string(),
+        iterations.to_string(),
+        format_duration(parse.median),
+        format_duration(stats.min),
+        format_duration(stats.median),
+        format_duration(stats.mean),
+        format_duration(stats.stddev),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use color_eyre::eyre::bail;
+    use pretty_assertions::assert_eq;
+
+    use crate::bench::bench_job;
+    use crate::cli::{Cli, Command};
+    use crate::registry::{Registration, Registry};
+    use crate::runner;
+    use crate::shared::{FakeDay, PartSolution};
+
+    #[test]
+    fn failed_parts_are_not_timed() {
+        let registry = Registry::new(vec![Registration {
+            module: "failing",
+            solution: Box::new(
+                FakeDay::new(
+                    1,
+                    |input| Ok(input.parse::<u32>()?),
+                    |&input, _| Ok(PartSolution::U32(input)),
+                    |_, _| bail!("No answer"),
+                )
+                .with_embedded_input("3"),
+            ),
+        }])
+        .unwrap();
+
+        let cli = Cli::try_parse_from(["aoc", "bench", "-n", "3"]).unwrap();
+
+        let Some(Command::Bench(ref args)) = cli.command else {
+            panic!("Not a bench command");
+        };
+
+        let rows: Vec<[String; 8]> = runner::jobs(&registry, &args.selection)
+            .unwrap()
+            .iter()
+            .map(|job| bench_job(job, args.iterations))
+            .collect();
+
+        assert!(rows[0].iter().skip(3).all(|cell| cell != "-"));
+        assert_eq!(
+            rows[1],
+            ["1", "2", "3", "-", "failed: No answer", "-", "-", "-"]
+        );
+    }
+}
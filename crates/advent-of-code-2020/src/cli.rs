@@ -0,0 +1,636 @@
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::input::InputSource;
+use crate::shared::Part;
+
+/// The answers file, relative to the working directory. The workspace's `aoc.toml` points at the one that is checked in
+/// next to the solutions.
+pub const DEFAULT_ANSWERS: &str = "answers.toml";
+
+/// The `profiling/` directory, relative to the working directory.
+const DEFAULT_PROFILING_DIR: &str = "profiling";
+
+#[derive(Parser, Debug)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// Read settings from this file instead of `aoc.toml`, flags given here take precedence over them.
+    #[arg(long, value_name = "PATH", env = "AOC_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Print the configuration the command would run with, after merging the file and the flags, and exit.
+    #[arg(long, global = true)]
+    pub show_config: bool,
+
+    /// How to print the results.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Show how the selected days that support it evolve while solving, on the terminal, or with `--visualize=DIR`
+    /// written to `DIR` as one file per frame.
+    #[arg(long, value_name = "DIR", num_args = 0..=1, require_equals = true, default_missing_value = "-")]
+    pub visualize: Option<PathBuf>,
+
+    /// How long each frame stays on the terminal when visualizing.
+    #[arg(long, value_name = "DURATION", default_value = "50ms", value_parser = parse_duration)]
+    pub frame_delay: Duration,
+}
+
+impl Cli {
+    pub fn selection(&self) -> &Selection {
+        match self.command {
+            Some(Command::Verify(ref args)) => &args.selection,
+            Some(Command::Bench(ref args)) => &args.selection,
+            Some(Command::Compare(ref args)) => &args.selection,
+            Some(Command::List | Command::Serve(_)) | None => &self.selection,
+        }
+    }
+
+    pub fn selection_mut(&mut self) -> &mut Selection {
+        match self.command {
+            Some(Command::Verify(ref mut args)) => &mut args.selection,
+            Some(Command::Bench(ref mut args)) => &mut args.selection,
+            Some(Command::Compare(ref mut args)) => &mut args.selection,
+            Some(Command::List | Command::Serve(_)) | None => &mut self.selection,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run the selected days and compare their answers against the answers file.
+    Verify(VerifyArgs),
+    /// Run each selected part repeatedly and report timing statistics.
+    Bench(BenchArgs),
+    /// Run every implementation of the selected parts, check that they agree and compare their timings.
+    Compare(CompareArgs),
+    /// List the registered days with their titles and parameters.
+    List,
+    /// Answer HTTP requests to solve parts: `GET /days` lists the days, `POST /days/{day}/parts/{part}` solves a part
+    /// for the input in the body.
+    Serve(ServeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// The answers file to verify against.
+    #[arg(long, value_name = "PATH", default_value = DEFAULT_ANSWERS)]
+    pub answers: PathBuf,
+
+    /// Write answers that are missing from the answers file into it.
+    #[arg(long)]
+    pub record: bool,
+
+    /// How to print the results.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Human readable lines, or a table when verifying.
+    Text,
+    /// A single JSON array with one record per part.
+    Json,
+    /// One JSON record per part, per line.
+    Ndjson,
+}
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// How many times to run each part.
+    #[arg(long, short = 'n', default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+
+    /// Also write the report to a file in this directory, defaults to the `profiling/` directory.
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = DEFAULT_PROFILING_DIR)]
+    pub save: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// How many times to run each implementation.
+    #[arg(long, short = 'n', default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub iterations: u32,
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// The address to listen on.
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1:8080")]
+    pub listen: SocketAddr,
+
+    /// The largest input a request may send, in bytes.
+    #[arg(long, value_name = "BYTES", default_value_t = 1024 * 1024)]
+    pub max_body_size: u64,
+
+    /// How long a request may take to arrive, and then how long solving it may take.
+    #[arg(long, value_name = "DURATION", default_value = "10s", value_parser = parse_duration)]
+    pub timeout: Duration,
+
+    /// How many requests are handled at once, a request that timed out counts until its part stops running.
+    #[arg(long, value_name = "N", default_value = "4")]
+    pub max_connections: NonZeroUsize,
+}
+
+#[derive(Args, Debug)]
+pub struct Selection {
+    /// Days to run: a single day (`11`), a range (`3-7`), a comma separated list of those (`1,3-5`) or `all`.
+    #[arg(value_name = "DAYS", default_value = "all")]
+    pub days: Vec<DaySelection>,
+
+    /// Only run this part of each selected day that has it: `1`, `2` or the name of a bonus part.
+    #[arg(long, short)]
+    pub part: Option<Part>,
+
+    /// Also run the bonus parts of the selected days.
+    #[arg(long, conflicts_with = "part")]
+    pub bonus: bool,
+
+    /// Read the puzzle input from this file instead of the embedded one, `-` reads from stdin.
+    #[arg(long, short, value_name = "PATH", conflicts_with = "inputs_dir")]
+    pub input: Option<PathBuf>,
+
+    /// Read the puzzle input of each day from `<DIR>/day_XX.txt` instead of the embedded one.
+    #[arg(long, value_name = "DIR")]
+    pub inputs_dir: Option<PathBuf>,
+
+    /// How many parts to solve concurrently, results are still reported in day and part order.
+    #[arg(long, short, value_name = "N", default_value_t = NonZeroUsize::MIN)]
+    pub jobs: NonZeroUsize,
+
+    /// Give up on a part that takes longer than this, e.g. `500ms`, `10s` or `2m`, seconds without a unit.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub timeout: Option<Duration>,
+
+    /// Give up on `PART` of `DAY` after this instead of `--timeout`, e.g. `11:2=500ms`. Given more than once for the
+    /// same part, the last one counts.
+    #[arg(long = "part-timeout", value_name = "DAY:PART=DURATION", value_parser = parse_part_timeout)]
+    pub part_timeouts: Vec<PartTimeout>,
+
+    /// Override a parameter of every selected day that has it, or only of `DAY`, e.g. `preamble=5` or `9:preamble=5`.
+    #[arg(long = "param", value_name = "[DAY:]NAME=VALUE", value_parser = parse_param)]
+    pub params: Vec<ParamOverride>,
+
+    /// Print how each part reached its answer under the answer, for the days that explain themselves.
+    #[arg(long)]
+    pub explain: bool,
+}
+
+impl Selection {
+    /// Resolves the requested days against the days that are `available`, in ascending order.
+    pub fn selected_days(&self, available: &BTreeSet<u32>) -> Result<Vec<u32>, color_eyre::Report> {
+        let mut selected = BTreeSet::new();
+
+        for selection in &self.days {
+            match *selection {
+                DaySelection::All => selected.extend(available.iter().copied()),
+                DaySelection::Days(ref days) => selected.extend(days.iter().copied()),
+            }
+        }
+
+        let missing: Vec<String> = selected
+            .difference(available)
+            .map(ToString::to_string)
+            .collect();
+
+        if !missing.is_empty() {
+            bail!(
+                "Day(s) {} not available, choose from {}",
+                missing.join(", "),
+                available
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        Ok(selected.into_iter().collect())
+    }
+
+    pub fn input_source(&self) -> InputSource {
+        InputSource::new(self.input.as_deref(), self.inputs_dir.as_deref())
+    }
+
+    /// The time budget of `part` of `day`, if it has one.
+    pub fn timeout(&self, day: u32, part: &Part) -> Option<Duration> {
+        self.part_timeouts
+            .iter()
+            .rev()
+            .find(|budget| budget.day == day && budget.part == *part)
+            .map_or(self.timeout, |budget| Some(budget.timeout))
+    }
+
+    pub fn runs_part(&self, part: &Part) -> bool {
+        match self.part {
+            Some(ref only) => only == part,
+            None => self.bonus || matches!(*part, Part::Puzzle(_)),
+        }
+    }
+}
+
+/// A parameter value given with `--param`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamOverride {
+    /// The only day it applies to, when given.
+    pub day: Option<u32>,
+    pub name: String,
+    pub value: String,
+}
+
+/// The time budget of a single part, given with `--part-timeout`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartTimeout {
+    pub day: u32,
+    pub part: Part,
+    pub timeout: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DaySelection {
+    All,
+    Days(BTreeSet<u32>),
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("all") {
+            return Ok(DaySelection::All);
+        }
+
+        let mut days = BTreeSet::new();
+
+        for piece in s.split(',').map(str::trim) {
+            days.extend(parse_day_range(piece)?);
+        }
+
+        Ok(DaySelection::Days(days))
+    }
+}
+
+pub fn parse_day(day: &str) -> Result<u32, String> {
+    match day.trim().parse::<u32>() {
+        Ok(0) | Err(_) => Err(format!("`{}` is not a valid day", day)),
+        Ok(day) => Ok(day),
+    }
+}
+
+fn parse_day_range(piece: &str) -> Result<RangeInclusive<u32>, String> {
+    let Some((start, end)) = piece.split_once('-') else {
+        let day = parse_day(piece)?;
+
+        return Ok(day..=day);
+    };
+
+    let start = parse_day(start)?;
+    let end = parse_day(end)?;
+
+    if start > end {
+        return Err(format!(
+            "`{}` is not a valid range, {} > {}",
+            piece, start, end
+        ));
+    }
+
+    Ok(start..=end)
+}
+
+fn parse_param(param: &str) -> Result<ParamOverride, String> {
+    let Some((target, value)) = param.split_once('=') else {
+        return Err(format!("`{}` is not of the form `[DAY:]NAME=VALUE`", param));
+    };
+
+    let (day, name) = match target.split_once(':') {
+        Some((day, name)) => (Some(parse_day(day)?), name.trim()),
+        None => (None, target.trim()),
+    };
+
+    if name.is_empty() {
+        return Err(format!("`{}` doesn't name a parameter", param));
+    }
+
+    Ok(ParamOverride {
+        day,
+        name: name.to_owned(),
+        value: value.to_owned(),
+    })
+}
+
+fn parse_part_timeout(budget: &str) -> Result<PartTimeout, String> {
+    let invalid = || format!("`{}` is not of the form `DAY:PART=DURATION`", budget);
+
+    let (target, timeout) = budget.split_once('=').ok_or_else(invalid)?;
+    let (day, part) = target.split_once(':').ok_or_else(invalid)?;
+
+    Ok(PartTimeout {
+        day: parse_day(day)?,
+        part: part.trim().parse()?,
+        timeout: parse_duration(timeout)?,
+    })
+}
+
+pub fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let duration = duration.trim();
+
+    let split = duration
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(duration.len());
+
+    let (value, unit) = duration.split_at(split);
+
+    let value = value
+        .parse::<f64>()
+        .map_err(|_| format!("`{}` is not a valid duration", duration))?;
+
+    let seconds = match unit {
+        "ms" => value / 1_000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        _ => return Err(format!("Unknown unit `{}`, use `ms`, `s` or `m`", unit)),
+    };
+
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|duration| !duration.is_zero())
+        .ok_or_else(|| format!("`{}` is not a valid duration", duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use clap::Parser as _;
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::{
+        Cli, Command, DaySelection, Format, ParamOverride, PartTimeout, parse_duration,
+        parse_param, parse_part_timeout,
+    };
+    use crate::shared::Part;
+
+    fn available() -> BTreeSet<u32> {
+        (1..=13).collect()
+    }
+
+    #[test]
+    fn single_day() {
+        assert_eq!(
+            "11".parse::<DaySelection>(),
+            Ok(DaySelection::Days(BTreeSet::from([11])))
+        );
+    }
+
+    #[test]
+    fn range_and_list() {
+        assert_eq!(
+            "1,3-5".parse::<DaySelection>(),
+            Ok(DaySelection::Days(BTreeSet::from([1, 3, 4, 5])))
+        );
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!("all".parse::<DaySelection>(), Ok(DaySelection::All));
+    }
+
+    #[test]
+    fn invalid_selections() {
+        "7-3".parse::<DaySelection>().unwrap_err();
+        "0".parse::<DaySelection>().unwrap_err();
+        "seven".parse::<DaySelection>().unwrap_err();
+        "1-".parse::<DaySelection>().unwrap_err();
+    }
+
+    #[test]
+    fn defaults_to_all_days_and_parts() {
+        let cli = Cli::try_parse_from(["aoc"]).unwrap();
+
+        assert_eq!(
+            cli.selection().selected_days(&available()).unwrap(),
+            (1..=13).collect::<Vec<_>>()
+        );
+        assert!(cli.selection().runs_part(&Part::Puzzle(1)));
+        assert!(cli.selection().runs_part(&Part::Puzzle(2)));
+        assert!(!cli.selection().runs_part(&Part::Bonus("torus".to_owned())));
+    }
+
+    #[test]
+    fn days_and_part() {
+        let cli = Cli::try_parse_from(["aoc", "11", "3-4", "--part", "2"]).unwrap();
+
+        assert_eq!(
+            cli.selection().selected_days(&available()).unwrap(),
+            vec![3, 4, 11]
+        );
+        assert!(!cli.selection().runs_part(&Part::Puzzle(1)));
+        assert!(cli.selection().runs_part(&Part::Puzzle(2)));
+    }
+
+    #[test]
+    fn bonus_parts() {
+        let cli = Cli::try_parse_from(["aoc", "11", "--part", "torus"]).unwrap();
+
+        assert!(!cli.selection().runs_part(&Part::Puzzle(1)));
+        assert!(cli.selection().runs_part(&Part::Bonus("torus".to_owned())));
+
+        let cli = Cli::try_parse_from(["aoc", "--bonus"]).unwrap();
+
+        assert!(cli.selection().runs_part(&Part::Puzzle(1)));
+        assert!(cli.selection().runs_part(&Part::Bonus("torus".to_owned())));
+
+        Cli::try_parse_from(["aoc", "--bonus", "--part", "1"]).unwrap_err();
+    }
+
+    #[test]
+    fn unavailable_day() {
+        let cli = Cli::try_parse_from(["aoc", "12-14"]).unwrap();
+
+        assert_eq!(
+            cli.selection()
+                .selected_days(&available())
+                .unwrap_err()
+                .to_string(),
+            "Day(s) 14 not available, choose from 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13"
+        );
+    }
+
+    #[test]
+    fn verify_with_selection() {
+        let cli = Cli::try_parse_from(["aoc", "verify", "7", "--record"]).unwrap();
+
+        assert!(matches!(cli.command, Some(Command::Verify(ref args)) if args.record));
+        assert_eq!(
+            cli.selection().selected_days(&available()).unwrap(),
+            vec![7]
+        );
+    }
+
+    #[test]
+    fn bench_options() {
+        let cli = Cli::try_parse_from(["aoc", "bench", "1", "-n", "3", "--save"]).unwrap();
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::Bench(ref args)) if args.iterations == 3 && args.save.is_some()
+        ));
+
+        Cli::try_parse_from(["aoc", "bench", "-n", "0"]).unwrap_err();
+    }
+
+    #[test]
+    fn default_paths_are_relative() {
+        let Some(Command::Verify(verify)) = Cli::try_parse_from(["aoc", "verify"]).unwrap().command
+        else {
+            panic!("Not a verify command");
+        };
+
+        let Some(Command::Bench(bench)) = Cli::try_parse_from(["aoc", "bench", "--save"])
+            .unwrap()
+            .command
+        else {
+            panic!("Not a bench command");
+        };
+
+        // resolved against the working directory, whichever machine the binary was built on
+        assert_eq!(verify.answers, PathBuf::from("answers.toml"));
+        assert_eq!(bench.save, Some(PathBuf::from("profiling")));
+    }
+
+    #[test]
+    fn format() {
+        let cli = Cli::try_parse_from(["aoc", "--format", "ndjson"]).unwrap();
+        assert_eq!(cli.format, Format::Ndjson);
+
+        let cli = Cli::try_parse_from(["aoc", "verify", "--format", "json"]).unwrap();
+        assert!(
+            matches!(cli.command, Some(Command::Verify(ref args)) if args.format == Format::Json)
+        );
+    }
+
+    #[test]
+    fn visualize() {
+        let cli = Cli::try_parse_from(["aoc", "--visualize", "11"]).unwrap();
+
+        assert_eq!(cli.visualize, Some(PathBuf::from("-")));
+        assert_eq!(
+            cli.selection().selected_days(&available()).unwrap(),
+            vec![11]
+        );
+
+        let cli =
+            Cli::try_parse_from(["aoc", "--visualize=frames", "--frame-delay", "1s"]).unwrap();
+
+        assert_eq!(cli.visualize, Some(PathBuf::from("frames")));
+        assert_eq!(cli.frame_delay, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn input_and_inputs_dir_conflict() {
+        Cli::try_parse_from(["aoc", "--input", "-", "--inputs-dir", "inputs"]).unwrap_err();
+    }
+
+    #[test]
+    fn invalid_part() {
+        Cli::try_parse_from(["aoc", "--part", "3"]).unwrap_err();
+        Cli::try_parse_from(["aoc", "--part", "k=4"]).unwrap_err();
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1_500)));
+        assert_eq!(parse_duration("2"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+
+        parse_duration("0s").unwrap_err();
+        parse_duration("5h").unwrap_err();
+        parse_duration("soon").unwrap_err();
+    }
+
+    #[test]
+    fn params() {
+        assert_eq!(
+            parse_param("preamble=5"),
+            Ok(ParamOverride {
+                day: None,
+                name: "preamble".to_owned(),
+                value: "5".to_owned(),
+            })
+        );
+        assert_eq!(
+            parse_param("7:bag=shiny gold"),
+            Ok(ParamOverride {
+                day: Some(7),
+                name: "bag".to_owned(),
+                value: "shiny gold".to_owned(),
+            })
+        );
+        assert_eq!(
+            parse_param("preamble"),
+            Err("`preamble` is not of the form `[DAY:]NAME=VALUE`".to_owned())
+        );
+        assert_eq!(
+            parse_param("0:preamble=5"),
+            Err("`0` is not a valid day".to_owned())
+        );
+        assert_eq!(
+            parse_param("9:=5"),
+            Err("`9:=5` doesn't name a parameter".to_owned())
+        );
+
+        let cli =
+            Cli::try_parse_from(["aoc", "9", "--param", "preamble=5", "--param", "9:x=1"]).unwrap();
+
+        assert_eq!(cli.selection.params.len(), 2);
+    }
+
+    #[test]
+    fn part_timeouts() {
+        assert_eq!(
+            parse_part_timeout("11:2=500ms"),
+            Ok(PartTimeout {
+                day: 11,
+                part: Part::Puzzle(2),
+                timeout: Duration::from_millis(500),
+            })
+        );
+        assert_eq!(
+            parse_part_timeout("11=500ms"),
+            Err("`11=500ms` is not of the form `DAY:PART=DURATION`".to_owned())
+        );
+        assert_eq!(
+            parse_part_timeout("11:2=soon"),
+            Err("`soon` is not a valid duration".to_owned())
+        );
+    }
+}
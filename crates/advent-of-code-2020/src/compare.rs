@@ -0,0 +1,281 @@
+use std::process::ExitCode;
+
+use crate::cli::CompareArgs;
+use crate::registry::Registry;
+use crate::runner::{self, Job, Outcome};
+use crate::shared::{MAIN_IMPLEMENTATION, ParsedInput, Part};
+use crate::table::render_table;
+use crate::timing::{Stats, format_duration};
+
+/// What one implementation of a part answered, and how long it took.
+struct Run {
+    day: u32,
+    part: Part,
+    implementation: &'static str,
+    outcome: Outcome,
+    /// `None` when the part wasn't solved, which isn't worth timing.
+    stats: Option<Stats>,
+}
+
+pub fn compare(solutions: &Registry, args: &CompareArgs) -> Result<ExitCode, color_eyre::Report> {
+    let runs = runs(solutions, args)?;
+
+    let (rows, disagreements) = tabulate(&runs);
+
+    print!(
+        "{}",
+        render_table(
+            &[
+                "Day",
+                "Part",
+                "Implementation",
+                "Answer",
+                "Min",
+                "Median",
+                "Relative"
+            ],
+            &rows
+        )
+    );
+
+    for disagreement in &disagreements {
+        eprintln!("{}", disagreement);
+    }
+
+    Ok(if disagreements.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Runs every implementation of every selected part, the main implementation of a part comes first.
+fn runs(solutions: &Registry, args: &CompareArgs) -> Result<Vec<Run>, color_eyre::Report> {
+    let jobs: Vec<Job<'_>> = runner::jobs(solutions, &args.selection)?
+        .iter()
+        .flat_map(|job| {
+            job.solution
+                .implementations(&job.part)
+                .into_iter()
+                .map(|implementation| job.with_implementation(implementation))
+        })
+        .collect();
+
+    let mut runs = Vec::with_capacity(jobs.len());
+
+    runner::schedule(
+        &jobs,
+        args.selection.jobs,
+        |job| run(job, args.iterations),
+        |run| runs.push(run),
+    );
+
+    Ok(runs)
+}
+
+/// Parses the input once and solves the part `iterations` times, the answer is the one of the first iteration.
+///
+/// Stops at the first iteration that doesn't solve the part, like `bench` does, and reports that outcome instead.
+fn run(job: &Job<'_>, iterations: u32) -> Run {
+    let (outcome, stats) = match job.parse().0 {
+        Ok(parsed) => solve(job, &parsed, iterations),
+        Err(outcome) => (outcome, None),
+    };
+
+    Run {
+        day: job.day,
+        part: job.part.clone(),
+        implementation: job.implementation,
+        outcome,
+        stats,
+    }
+}
+
+fn solve(job: &Job<'_>, parsed: &ParsedInput, iterations: u32) -> (Outcome, Option<Stats>) {
+    let (outcome, first) = job.solve_parsed(parsed);
+
+    if !matches!(outcome, Outcome::Solved(_)) {
+        return (outcome, None);
+    }
+
+    let mut samples = vec![first];
+
+    for _ in 1..iterations {
+        let (again, sample) = job.solve_parsed(parsed);
+
+        if !matches!(again, Outcome::Solved(_)) {
+            return (again, None);
+        }
+
+        samples.push(sample);
+    }
+
+    (outcome, Stats::new(&samples))
+}
+
+/// Renders the runs as table rows, timed relative to the part's main implementation, and describes every
+/// implementation that doesn't agree with it.
+fn tabulate(runs: &[Run]) -> (Vec<[String; 7]>, Vec<String>) {
+    let mut rows = Vec::with_capacity(runs.len());
+    let mut disagreements = Vec::new();
+
+    let mut main: Option<&Run> = None;
+
+    for run in runs {
+        if run.implementation == MAIN_IMPLEMENTATION {
+            main = Some(run);
+        }
+
+        let main = main.expect("The main implementation of a part comes first");
+
+        let agrees = match (&main.outcome, &run.outcome) {
+            (&Outcome::Solved(ref expected), &Outcome::Solved(ref answer)) => expected == answer,
+            _ => false,
+        };
+
+        if run.implementation != MAIN_IMPLEMENTATION && !agrees {
+            disagreements.push(format!(
+                "Day {}, part {}: `{}` answered {}, `{}` answered {}",
+                run.day,
+                run.part,
+                run.implementation,
+                run.outcome.describe(),
+                main.implementation,
+                main.outcome.describe()
+            ));
+        }
+
+        let relative = match (&main.stats, &run.stats) {
+            (&Some(ref main), &Some(ref stats)) if !main.median.is_zero() => format!(
+                "{:.2}x",
+                stats.median.as_secs_f64() / main.median.as_secs_f64()
+            ),
+            _ => "-".to_owned(),
+        };
+
+        let (fastest, median) = run.stats.as_ref().map_or_else(
+            || ("-".to_owned(), "-".to_owned()),
+            |stats| (format_duration(stats.min), format_duration(stats.median)),
+        );
+
+        rows.push([
+            run.day.to_string(),
+            run.part.to_string(),
+            run.implementation.to_owned(),
+            run.outcome.describe(),
+            fastest,
+            median,
+            relative,
+        ]);
+    }
+
+    (rows, disagreements)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use color_eyre::eyre::bail;
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::{Cli, Command};
+    use crate::compare::{runs, tabulate};
+    use crate::registry::{Registration, Registry};
+    use crate::shared::{FakeDay, Implementation, PartSolution};
+
+    #[test]
+    fn implementations_are_checked_against_main() {
+        let registry = Registry::new(vec![Registration {
+            module: "doubling",
+            solution: Box::new(
+                FakeDay::new(
+                    1,
+                    |input| Ok(input.parse::<u32>()?),
+                    |&input, _| Ok(PartSolution::U32(input * 2)),
+                    |&input, _| Ok(PartSolution::U32(input)),
+                )
+                .with_embedded_input("3")
+                .with_implementations(&[
+                    Implementation {
+                        name: "added",
+                        part: 1,
+                        solve: |_, input, _| Ok(PartSolution::U64(u64::from(input + input))),
+                    },
+                    Implementation {
+                        name: "squared",
+                        part: 1,
+                        solve: |_, input, _| Ok(PartSolution::U32(input * input)),
+                    },
+                ]),
+            ),
+        }])
+        .unwrap();
+
+        let cli = Cli::try_parse_from(["aoc", "compare", "-n", "3"]).unwrap();
+
+        let Some(Command::Compare(ref args)) = cli.command else {
+            panic!("Not a compare command");
+        };
+
+        let (rows, disagreements) = tabulate(&runs(&registry, args).unwrap());
+
+        let answers: Vec<[&str; 4]> = rows
+            .iter()
+            .map(|row| [&*row[0], &*row[1], &*row[2], &*row[3]])
+            .collect();
+
+        assert_eq!(
+            answers,
+            vec![
+                ["1", "1", "main", "6"],
+                ["1", "1", "added", "6"],
+                ["1", "1", "squared", "9"],
+                ["1", "2", "main", "3"],
+            ]
+        );
+        assert_eq!(
+            disagreements,
+            vec!["Day 1, part 1: `squared` answered 9, `main` answered 6"]
+        );
+    }
+
+    #[test]
+    fn failed_implementations_are_not_timed() {
+        let registry = Registry::new(vec![Registration {
+            module: "failing",
+            solution: Box::new(
+                FakeDay::new(
+                    1,
+                    |input| Ok(input.parse::<u32>()?),
+                    |&input, _| Ok(PartSolution::U32(input)),
+                    |&input, _| Ok(PartSolution::U32(input)),
+                )
+                .with_embedded_input("3")
+                .with_implementations(&[Implementation {
+                    name: "failing",
+                    part: 1,
+                    solve: |_, _, _| bail!("No answer"),
+                }]),
+            ),
+        }])
+        .unwrap();
+
+        let cli = Cli::try_parse_from(["aoc", "compare", "-n", "3", "--part", "1"]).unwrap();
+
+        let Some(Command::Compare(ref args)) = cli.command else {
+            panic!("Not a compare command");
+        };
+
+        let (rows, disagreements) = tabulate(&runs(&registry, args).unwrap());
+
+        assert!(rows[0].iter().skip(4).all(|cell| cell != "-"));
+        assert_eq!(
+            rows[1],
+            ["1", "1", "failing", "failed: No answer", "-", "-", "-"]
+        );
+        assert_eq!(
+            disagreements,
+            vec!["Day 1, part 1: `failing` answered failed: No answer, `main` answered 3"]
+        );
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use color_eyre::eyre::{WrapErr as _, eyre};
+use serde::{Deserialize, Serialize};
+use toml::Value;
+use toml::de::Error as TomlError;
+
+use crate::answers::{day_key, part_key};
+use crate::cli::{
+    Cli, Command, DEFAULT_ANSWERS, Format, ParamOverride, PartTimeout, parse_day, parse_duration,
+};
+use crate::registry::Registry;
+use crate::runner::day_params;
+use crate::shared::Part;
+use crate::timing::format_duration;
+
+/// The configuration file that is read when it exists and `--config` doesn't name another one.
+const DEFAULT_CONFIG: &str = "aoc.toml";
+
+/// The settings of a configuration file, all of them optional, flags given on the command line take precedence.
+///
+/// ```toml
+/// inputs_dir = "inputs"
+/// format = "json"
+/// jobs = 4
+/// timeout = "10s"
+///
+/// [timeouts.day_11]
+/// part_2 = "2s"
+///
+/// [params.day_09]
+/// preamble = 5
+/// ```
+#[derive(Default, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Where the puzzle inputs are read from, as `<DIR>/day_XX.txt`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inputs_dir: Option<PathBuf>,
+    /// The answers file to verify against.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answers: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<Format>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<NonZeroUsize>,
+    /// The time budget of every part.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    /// Time budgets of single parts, by day (`day_11`) and part (`part_2`), they take precedence over `timeout`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub timeouts: BTreeMap<String, BTreeMap<String, String>>,
+    /// Parameter values by day (`day_09`) and parameter name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl Config {
+    /// Loads the configuration file at `path`, or `aoc.toml` when there is one. Paths in the file are relative to it,
+    /// and the keys it doesn't know are reported and ignored.
+    pub fn load(path: Option<&Path>) -> Result<Self, color_eyre::Report> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(DEFAULT_CONFIG).exists() => Path::new(DEFAULT_CONFIG),
+            None => return Ok(Config::default()),
+        };
+
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read configuration from {}", path.display()))?;
+
+        let (mut config, unknown) = Config::parse(&contents)
+            .wrap_err_with(|| format!("Failed to parse configuration in {}", path.display()))?;
+
+        for key in unknown {
+            eprintln!("Ignoring unknown key `{}` in {}", key, path.display());
+        }
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        config.inputs_dir = config.inputs_dir.map(|inputs_dir| dir.join(inputs_dir));
+        config.answers = config.answers.map(|answers| dir.join(answers));
+
+        Ok(config)
+    }
+
+    /// Parses a configuration, together with the keys that were ignored because they don't mean anything.
+    fn parse(contents: &str) -> Result<(Self, Vec<String>), TomlError> {
+        let mut unknown = Vec::new();
+
+        let config = serde_ignored::deserialize(toml::Deserializer::parse(contents)?, |key| {
+            unknown.push(key.to_string());
+        })?;
+
+        Ok((config, unknown))
+    }
+
+    /// Fills in the settings of `cli` that weren't given on the command line, `matches` tells which ones were.
+    pub fn apply(&self, cli: &mut Cli, matches: &ArgMatches) -> Result<(), color_eyre::Report> {
+        let matches = matches.subcommand().map_or(matches, |(_, matches)| matches);
+
+        let defaulted = |id: &str| {
+            matches!(
+                matches.value_source(id),
+                None | Some(ValueSource::DefaultValue)
+            )
+        };
+
+        match cli.command {
+            Some(Command::Verify(ref mut args)) => {
+                if let Some(ref answers) = self.answers
+                    && defaulted("answers")
+                {
+                    args.answers.clone_from(answers);
+                }
+
+                if let Some(format) = self.format
+                    && defaulted("format")
+                {
+                    args.format = format;
+                }
+            },
+            None => {
+                if let Some(format) = self.format
+                    && defaulted("format")
+                {
+                    cli.format = format;
+                }
+            },
+            Some(Command::Bench(_) | Command::Compare(_) | Command::List | Command::Serve(_)) => {},
+        }
+
+        let selection = cli.selection_mut();
+
+        if selection.input.is_none() && selection.inputs_dir.is_none() {
+            selection.inputs_dir.clone_from(&self.inputs_dir);
+        }
+
+        if let Some(jobs) = self.jobs
+            && defaulted("jobs")
+        {
+            selection.jobs = jobs;
+        }
+
+        // `--timeout` replaces every budget of the file, not only its `timeout`
+        let mut part_timeouts = if selection.timeout.is_some() {
+            Vec::new()
+        } else {
+            self.part_timeouts()?
+        };
+
+        if selection.timeout.is_none() {
+            selection.timeout = self
+                .timeout
+                .as_deref()
+                .map(|timeout| {
+                    parse_duration(timeout).map_err(|error| eyre!("Invalid `timeout`: {}", error))
+                })
+                .transpose()?;
+        }
+
+        // the command line's values come last, so they win
+        part_timeouts.append(&mut selection.part_timeouts);
+        selection.part_timeouts = part_timeouts;
+
+        let mut params = self.param_overrides()?;
+        params.append(&mut selection.params);
+        selection.params = params;
+
+        Ok(())
+    }
+
+    fn part_timeouts(&self) -> Result<Vec<PartTimeout>, color_eyre::Report> {
+        let mut timeouts = Vec::new();
+
+        for (day, parts) in &self.timeouts {
+            let day = parse_day_key(day)?;
+
+            for (part, timeout) in parts {
+                let part = parse_part_key(part)?;
+
+                let timeout = parse_duration(timeout).map_err(|error| {
+                    eyre!("Invalid timeout of day {}, part {}: {}", day, part, error)
+                })?;
+
+                timeouts.push(PartTimeout { day, part, timeout });
+            }
+        }
+
+        Ok(timeouts)
+    }
+
+    fn param_overrides(&self) -> Result<Vec<ParamOverride>, color_eyre::Report> {
+        let mut overrides = Vec::new();
+
+        for (day, params) in &self.params {
+            let day = parse_day_key(day)?;
+
+            for (name, value) in params {
+                overrides.push(ParamOverride {
+                    day: Some(day),
+                    name: name.clone(),
+                    // strings are taken as they are, without quotes
+                    value: value
+                        .as_str()
+                        .map_or_else(|| value.to_string(), str::to_owned),
+                });
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// The configuration `cli` runs with once this file is applied, with the parameters each selected day ends up
+    /// with.
+    pub fn effective(&self, cli: &Cli, solutions: &Registry) -> Result<Self, color_eyre::Report> {
+        let selection = cli.selection();
+
+        let (answers, format) = match cli.command {
+            Some(Command::Verify(ref args)) => (args.answers.clone(), args.format),
+            Some(Command::Bench(_) | Command::Compare(_) | Command::List | Command::Serve(_))
+            | None => (
+                self.answers
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from(DEFAULT_ANSWERS)),
+                cli.format,
+            ),
+        };
+
+        let mut timeouts: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+
+        // later budgets of the same part replace earlier ones, like they do when running
+        for budget in &selection.part_timeouts {
+            timeouts
+                .entry(day_key(budget.day))
+                .or_default()
+                .insert(part_key(&budget.part), format_duration(budget.timeout));
+        }
+
+        let mut params = BTreeMap::new();
+
+        for day in selection.selected_days(&solutions.days())? {
+            let values: BTreeMap<String, Value> =
+                day_params(&selection.params, day, solutions[day].params())?
+                    .into_iter()
+                    .map(|(name, value)| (name, Value::String(value)))
+                    .collect();
+
+            if !values.is_empty() {
+                params.insert(day_key(day), values);
+            }
+        }
+
+        Ok(Config {
+            inputs_dir: selection.inputs_dir.clone(),
+            answers: Some(answers),
+            format: Some(format),
+            jobs: Some(selection.jobs),
+            timeout: selection.timeout.map(format_duration),
+            timeouts,
+            params,
+        })
+    }
+}
+
+/// Reads a day the way the answers file names it, `day_09`.
+fn parse_day_key(key: &str) -> Result<u32, color_eyre::Report> {
+    key.strip_prefix("day_")
+        .ok_or_else(|| format!("`{}` is not a day, expected e.g. `day_09`", key))
+        .and_then(parse_day)
+        .map_err(|error| eyre!(error))
+}
+
+/// Reads a part the way the answers file names it, `part_2`.
+fn parse_part_key(key: &str) -> Result<Part, color_eyre::Report> {
+    key.strip_prefix("part_")
+        .ok_or_else(|| format!("`{}` is not a part, expected e.g. `part_2`", key))
+        .and_then(str::parse)
+        .map_err(|error| eyre!(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::num::NonZeroUsize;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use clap::{CommandFactory as _, FromArgMatches as _};
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::{Cli, Command, Format, ParamOverride, PartTimeout};
+    use crate::config::Config;
+    use crate::registrations;
+    use crate::registry::Registry;
+    use crate::shared::Part;
+
+    const CONFIG: &str = r#"
+inputs_dir = "inputs"
+answers = "verified.toml"
+format = "ndjson"
+jobs = 4
+timeout = "10s"
+colour = "always"
+
+[timeouts.day_11]
+part_2 = "500ms"
+
+[params.day_09]
+preamble = 5
+"#;
+
+    fn cli_with_config(args: &[&str]) -> (Cli, Config) {
+        let matches = Cli::command().try_get_matches_from(args).unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+
+        let (config, _) = Config::parse(CONFIG).unwrap();
+
+        config.apply(&mut cli, &matches).unwrap();
+
+        (cli, config)
+    }
+
+    #[test]
+    fn unknown_keys_are_reported() {
+        let (config, unknown) = Config::parse(CONFIG).unwrap();
+
+        assert_eq!(config.jobs, NonZeroUsize::new(4));
+        assert_eq!(unknown, vec!["colour"]);
+    }
+
+    #[test]
+    fn file_fills_in_what_is_not_given() {
+        let (cli, _) = cli_with_config(&["aoc", "9"]);
+
+        assert_eq!(cli.format, Format::Ndjson);
+        assert_eq!(cli.selection.inputs_dir, Some(PathBuf::from("inputs")));
+        assert_eq!(cli.selection.jobs, NonZeroUsize::new(4).unwrap());
+        assert_eq!(cli.selection.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(
+            cli.selection.part_timeouts,
+            vec![PartTimeout {
+                day: 11,
+                part: Part::Puzzle(2),
+                timeout: Duration::from_millis(500),
+            }]
+        );
+        assert_eq!(
+            cli.selection.params,
+            vec![ParamOverride {
+                day: Some(9),
+                name: "preamble".to_owned(),
+                value: "5".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn command_line_takes_precedence() {
+        let (cli, config) = cli_with_config(&[
+            "aoc",
+            "verify",
+            "9",
+            "--input",
+            "day_09.txt",
+            "-j",
+            "2",
+            "--timeout",
+            "1s",
+            "--part-timeout",
+            "13:1=2s",
+            "--param",
+            "preamble=7",
+            "--format",
+            "text",
+        ]);
+
+        let Some(Command::Verify(ref args)) = cli.command else {
+            panic!("Not a verify command");
+        };
+
+        assert_eq!(args.format, Format::Text);
+        assert_eq!(args.answers, PathBuf::from("verified.toml"));
+        assert_eq!(args.selection.inputs_dir, None);
+        assert_eq!(args.selection.jobs, NonZeroUsize::new(2).unwrap());
+        assert_eq!(args.selection.timeout, Some(Duration::from_secs(1)));
+        assert_eq!(
+            args.selection.part_timeouts,
+            vec![PartTimeout {
+                day: 13,
+                part: Part::Puzzle(1),
+                timeout: Duration::from_secs(2),
+            }]
+        );
+
+        let registry = Registry::new(registrations()).unwrap();
+
+        let effective = config.effective(&cli, &registry).unwrap();
+
+        assert_eq!(
+            toml::to_string(&effective).unwrap(),
+            r#"answers = "verified.toml"
+format = "text"
+jobs = 2
+timeout = "1.00s"
+
+[timeouts.day_13]
+part_1 = "2.00s"
+
+[params.day_09]
+preamble = "7"
+"#
+        );
+    }
+
+    #[test]
+    fn part_timeouts_on_the_command_line_win() {
+        let (cli, config) = cli_with_config(&["aoc", "11", "--part-timeout", "11:2=3s"]);
+
+        assert_eq!(
+            cli.selection.timeout(11, &Part::Puzzle(2)),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            cli.selection.timeout(11, &Part::Puzzle(1)),
+            Some(Duration::from_secs(10))
+        );
+
+        let registry = Registry::new(registrations()).unwrap();
+
+        let effective = config.effective(&cli, &registry).unwrap();
+
+        assert_eq!(
+            effective.timeouts,
+            BTreeMap::from([(
+                "day_11".to_owned(),
+                BTreeMap::from([("part_2".to_owned(), "3.00s".to_owned())])
+            )])
+        );
+    }
+
+    #[test]
+    fn answers_are_shown_without_verifying() {
+        let (cli, config) = cli_with_config(&["aoc", "--show-config", "9"]);
+
+        assert!(cli.show_config);
+
+        let registry = Registry::new(registrations()).unwrap();
+
+        let effective = config.effective(&cli, &registry).unwrap();
+
+        assert_eq!(effective.answers, Some(PathBuf::from("verified.toml")));
+        assert_eq!(effective.format, Some(Format::Ndjson));
+    }
+}
@@ -1,6 +1,7 @@
+use color_eyre::eyre::{WrapErr as _, eyre};
 use hashbrown::HashSet;
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{Context, Day, Metadata, Note, Param, PartSolution};
 
 fn k_sums_unsorted(mut nums: Vec<i32>, target: i32, k: usize) -> Vec<Vec<i32>> {
     nums.sort_unstable();
@@ -65,37 +66,100 @@ fn two_sum(nums: &[i32], target: i32) -> Vec<Vec<i32>> {
     result
 }
 
+fn explain_entries(context: &Context, entries: &[i32], target: i32) {
+    context.explain().note(|| {
+        let product: i32 = entries.iter().product();
+        let entries: Vec<String> = entries.iter().map(ToString::to_string).collect();
+
+        Note::new(format!("Found {} entries that sum up to the target", entries.len()))
+            .with("sum", format!("{} = {}", entries.join(" + "), target))
+            .with("product", format!("{} = {}", entries.join(" * "), product))
+    });
+}
+
+const TARGET: Param = Param {
+    name: "target",
+    default: "2020",
+    description: "What the entries need to sum up to.",
+};
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines = include_str!("day_01/input.txt");
+    type Input = Vec<i32>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 1,
+            title: "Report Repair",
+        }
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[TARGET]
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_01/input.txt")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        input
+            .lines()
+            .map(|s| {
+                s.parse::<i32>()
+                    .wrap_err_with(|| format!("`{}` is not a number", s))
+            })
+            .collect()
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let target = context.param(&TARGET)?;
+
+        let results = k_sums_unsorted(input.clone(), target, 2);
 
-        let numbers = lines.lines().map(|s| s.parse::<i32>().unwrap()).collect();
+        let result = results
+            .first()
+            .ok_or_else(|| eyre!("No 2 entries sum up to {}", target))?;
 
-        let results = k_sums_unsorted(numbers, 2020, 2);
+        explain_entries(context, result, target);
 
-        PartSolution::I32(results[0][0] * results[0][1])
+        Ok(PartSolution::I32(result[0] * result[1]))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines = include_str!("day_01/input.txt");
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let target = context.param(&TARGET)?;
 
-        let numbers = lines.lines().map(|s| s.parse::<i32>().unwrap()).collect();
+        let results = k_sums_unsorted(input.clone(), target, 3);
 
-        let results = k_sums_unsorted(numbers, 2020, 3);
+        let result = results
+            .first()
+            .ok_or_else(|| eyre!("No 3 entries sum up to {}", target))?;
 
-        PartSolution::I32(results[0][0] * results[0][1] * results[0][2])
+        explain_entries(context, result, target);
+
+        Ok(PartSolution::I32(result[0] * result[1] * result[2]))
     }
 }
 
 #[cfg(test)]
 mod tests {
     mod part_1 {
+        use std::sync::Arc;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_01::{Solution, k_sums_unsorted};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Cancellation, Context, Day as _, Explain, PartSolution};
 
         #[test]
         fn example() {
@@ -109,9 +173,46 @@ mod tests {
             assert_eq!(results[0][0] * results[0][1], 514_579);
         }
 
+        #[test]
+        fn explain() {
+            let solution = Solution {};
+
+            let context = Context::new(Cancellation::default(), Arc::default(), Explain::enabled());
+
+            solution
+                .part_1(
+                    &solution.parse("1721\n979\n366\n299\n675\n1456").unwrap(),
+                    &context,
+                )
+                .unwrap();
+
+            let notes: Vec<String> = context
+                .into_notes()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+
+            assert_eq!(
+                notes,
+                vec![
+                    "Found 2 entries that sum up to the target: sum = 1721 + 299 = 2020, product = 1721 * 299 = 514579"
+                ]
+            );
+        }
+
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::I32(1_019_571), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::I32(1_019_571),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
     }
 
@@ -119,7 +220,7 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_01::{Solution, k_sums_unsorted};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn example() {
@@ -135,7 +236,17 @@ mod tests {
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::I32(100_655_544), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::I32(100_655_544),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
     }
 }
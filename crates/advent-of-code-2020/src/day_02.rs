@@ -1,15 +1,20 @@
+use std::num::NonZeroUsize;
+
 use hashbrown::HashMap;
+use shared::parser::{
+    Cursor, ParseResult, character, integer, lines, literal, parse, try_map, word,
+};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{Context, Day, Metadata, PartSolution};
 
-struct RuleWithPasswordPart1 {
-    min: u32,
-    max: u32,
+struct RuleWithPasswordPart1<'p> {
+    min: NonZeroUsize,
+    max: NonZeroUsize,
     character: char,
-    password: String,
+    password: &'p str,
 }
 
-impl RuleWithPasswordPart1 {
+impl RuleWithPasswordPart1<'_> {
     pub fn is_valid(&self) -> bool {
         let mut counts = HashMap::new();
 
@@ -19,77 +24,106 @@ impl RuleWithPasswordPart1 {
 
         match counts.get(&self.character) {
             None => false,
-            Some(t) => (self.min <= *t) && (*t <= self.max),
+            Some(&t) => (self.min.get() <= t) && (t <= self.max.get()),
         }
     }
 }
 
-struct RuleWithPasswordPart2 {
-    first_position: usize,
-    second_position: usize,
+struct RuleWithPasswordPart2<'p> {
+    first_position: NonZeroUsize,
+    second_position: NonZeroUsize,
     character: char,
-    password: String,
+    password: &'p str,
 }
 
-impl RuleWithPasswordPart2 {
+impl RuleWithPasswordPart2<'_> {
     pub fn is_valid(&self) -> bool {
-        let p1_valid = self.password.chars().nth(self.first_position - 1) == Some(self.character);
-        let p2_valid = self.password.chars().nth(self.second_position - 1) == Some(self.character);
+        // positions start at 1
+        let p1_valid =
+            self.password.chars().nth(self.first_position.get() - 1) == Some(self.character);
+        let p2_valid =
+            self.password.chars().nth(self.second_position.get() - 1) == Some(self.character);
 
         p1_valid ^ p2_valid
     }
 }
 
-fn parse_line(line: &str) -> (usize, usize, char, String) {
+/// A count or a position, neither of them can be 0.
+fn above_zero(cursor: &mut Cursor<'_>) -> ParseResult<NonZeroUsize> {
+    try_map(integer, |number: usize| {
+        NonZeroUsize::new(number).ok_or_else(|| "a number above 0".to_owned())
+    })(cursor)
+}
+
+fn parse_line(cursor: &mut Cursor<'_>) -> ParseResult<(NonZeroUsize, NonZeroUsize, char, String)> {
     // grammar:
     // <min>-<max> char: <password>
-    let pieces: Vec<&str> = line.split(' ').collect();
-
-    let min_max_vec: Vec<usize> = pieces[0]
-        .split('-')
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
-
-    let char_with_colon_piece = pieces[1];
-    let password = pieces[2];
-
-    (
-        *min_max_vec.first().unwrap(),
-        *min_max_vec.get(1).unwrap(),
-        char_with_colon_piece.chars().next().unwrap(),
-        password.into(),
-    )
+    let min = above_zero(cursor)?;
+    literal("-")(cursor)?;
+    let max = try_map(above_zero, |max| {
+        if max < min {
+            Err(format!("a number of at least {}", min))
+        } else {
+            Ok(max)
+        }
+    })(cursor)?;
+    literal(" ")(cursor)?;
+    let letter = character(cursor)?;
+    literal(": ")(cursor)?;
+    let password = word(cursor)?;
+
+    Ok((min, max, letter, password.to_owned()))
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines = include_str!("day_02/input.txt");
-
-        let valid_passwords = lines
-            .lines()
-            .map(parse_line)
-            .map(|(min, max, character, password)| RuleWithPasswordPart1 {
-                min: u32::try_from(min).unwrap(),
-                max: u32::try_from(max).unwrap(),
+    type Input = Vec<(NonZeroUsize, NonZeroUsize, char, String)>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 2,
+            title: "Password Philosophy",
+        }
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_02/input.txt")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        Ok(parse(input, lines(parse_line))?)
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let valid_passwords = input
+            .iter()
+            .map(|&(min, max, character, ref password)| RuleWithPasswordPart1 {
+                min,
+                max,
                 character,
                 password,
             })
             .filter(RuleWithPasswordPart1::is_valid)
             .count();
 
-        PartSolution::USize(valid_passwords)
+        Ok(PartSolution::USize(valid_passwords))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines = include_str!("day_02/input.txt");
-
-        let valid_passwords = lines
-            .lines()
-            .map(parse_line)
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let valid_passwords = input
+            .iter()
             .map(
-                |(first_position, second_position, character, password)| RuleWithPasswordPart2 {
+                |&(first_position, second_position, character, ref password)| RuleWithPasswordPart2 {
                     first_position,
                     second_position,
                     character,
@@ -99,7 +133,7 @@ impl Day for Solution {
             .filter(RuleWithPasswordPart2::is_valid)
             .count();
 
-        PartSolution::USize(valid_passwords)
+        Ok(PartSolution::USize(valid_passwords))
     }
 }
 
@@ -109,11 +143,42 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_02::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(620), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(620),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn zero() {
+            let solution = Solution {};
+
+            assert_eq!(
+                format!("{:#}", solution.parse("1-3 a: abcde\n0-9 c: ccccc").unwrap_err()),
+                "Line 2, column 1: expected a number above 0, found `0-9 c: ccccc`"
+            );
+        }
+
+        #[test]
+        fn max_below_min() {
+            let solution = Solution {};
+
+            assert_eq!(
+                format!("{:#}", solution.parse("1-3 a: abcde
+5-3 c: ccccc").unwrap_err()),
+                "Line 2, column 3: expected a number of at least 5, found `3 c: ccccc`"
+            );
         }
     }
 
@@ -121,11 +186,21 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_02::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(727), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(727),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
     }
 }
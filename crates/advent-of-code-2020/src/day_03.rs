@@ -1,23 +1,45 @@
-use crate::shared::{Day, PartSolution};
+use std::str::FromStr;
+
+use color_eyre::eyre::{OptionExt as _, bail};
+use shared::grid::Grid;
+
+use crate::shared::{Context, Day, Implementation, Metadata, Param, PartSolution};
+
+/// What is on a square of the map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Square {
+    Open,
+    Tree,
+}
+
+impl Square {
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Square::Open),
+            '#' => Some(Square::Tree),
+            _ => None,
+        }
+    }
+}
 
 fn descent_and_go_right(
-    lines: &[String],
+    map: &Grid<Square>,
     row: usize,
     col: usize,
     mut trees: u32,
     down: usize,
     right: usize,
 ) -> u32 {
-    match lines.get(row) {
-        Some(line) => {
-            if line.chars().nth(col) == Some('#') {
+    match map.get((row, col)) {
+        Some(&square) => {
+            if square == Square::Tree {
                 trees += 1;
             }
 
             descent_and_go_right(
-                lines,
+                map,
                 row + down,
-                (col + right) % line.len(),
+                (col + right) % map.columns(),
                 trees,
                 down,
                 right,
@@ -27,31 +49,185 @@ fn descent_and_go_right(
     }
 }
 
+/// Counts the trees with an iterator over the rows that are visited, instead of recursing row by row.
+fn count_trees(map: &Grid<Square>, slope: &Slope) -> u32 {
+    (0..map.rows())
+        .step_by(slope.down)
+        .enumerate()
+        .map(|(step, row)| {
+            let row = map.row(row);
+
+            u32::from(row[(step * slope.right) % row.len()] == Square::Tree)
+        })
+        .sum()
+}
+
+/// Multiplies the trees of every slope, unusual slopes can hit more trees than fit in the answer.
+fn product<I>(trees: I) -> Result<u32, color_eyre::Report>
+where
+    I: IntoIterator<Item = u32>,
+{
+    trees
+        .into_iter()
+        .try_fold(1_u32, u32::checked_mul)
+        .ok_or_eyre("The product of the trees is too large")
+}
+
+fn part_1_iterator(
+    _solution: &Solution,
+    input: &<Solution as Day>::Input,
+    context: &Context,
+) -> Result<PartSolution, color_eyre::Report> {
+    let slope: Slope = context.param(&SLOPE)?;
+
+    Ok(PartSolution::U32(count_trees(input, &slope)))
+}
+
+fn part_2_iterator(
+    _solution: &Solution,
+    input: &<Solution as Day>::Input,
+    context: &Context,
+) -> Result<PartSolution, color_eyre::Report> {
+    let Slopes(slopes) = context.param(&SLOPES)?;
+
+    Ok(PartSolution::U32(product(
+        slopes.iter().map(|slope| count_trees(input, slope)),
+    )?))
+}
+
+/// How far the toboggan goes right for every step down.
+#[derive(Debug, PartialEq, Eq)]
+struct Slope {
+    right: usize,
+    down: usize,
+}
+
+impl FromStr for Slope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("`{}` is not a slope, expected `RIGHT,DOWN`", s);
+
+        let (right, down) = s.split_once(',').ok_or_else(invalid)?;
+
+        let right = right.trim().parse().map_err(|_| invalid())?;
+        let down = down.trim().parse().map_err(|_| invalid())?;
+
+        if down == 0 {
+            return Err(format!("`{}` never reaches the bottom", s));
+        }
+
+        Ok(Slope { right, down })
+    }
+}
+
+/// Slopes, separated by spaces, at least one.
+#[derive(Debug, PartialEq, Eq)]
+struct Slopes(Vec<Slope>);
+
+impl FromStr for Slopes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let slopes = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if slopes.is_empty() {
+            return Err("There are no slopes".to_owned());
+        }
+
+        Ok(Slopes(slopes))
+    }
+}
+
+const SLOPE: Param = Param {
+    name: "slope",
+    default: "3,1",
+    description: "The slope of part 1, as `RIGHT,DOWN`.",
+};
+
+const SLOPES: Param = Param {
+    name: "slopes",
+    default: "1,1 3,1 5,1 7,1 1,2",
+    description: "The slopes of part 2, separated by spaces.",
+};
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_03/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    type Input = Grid<Square>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 3,
+            title: "Toboggan Trajectory",
+        }
+    }
+
+    fn params(&self) -> &'static [Param] {
+        &[SLOPE, SLOPES]
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_03/input.txt")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        let map = Grid::parse(input, Square::parse)?;
 
-        PartSolution::U32(descent_and_go_right(&lines, 0, 0, 0, 1, 3))
+        // the map repeats to the right, which a map without columns can't
+        if map.columns() == 0 {
+            bail!("The map has no columns");
+        }
+
+        Ok(map)
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let slope: Slope = context.param(&SLOPE)?;
+
+        Ok(PartSolution::U32(descent_and_go_right(
+            input,
+            0,
+            0,
+            0,
+            slope.down,
+            slope.right,
+        )))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_03/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let Slopes(slopes) = context.param(&SLOPES)?;
 
-        let result1 = descent_and_go_right(&lines, 0, 0, 0, 1, 1);
-        let result2 = descent_and_go_right(&lines, 0, 0, 0, 1, 3);
-        let result3 = descent_and_go_right(&lines, 0, 0, 0, 1, 5);
-        let result4 = descent_and_go_right(&lines, 0, 0, 0, 1, 7);
-        let result5 = descent_and_go_right(&lines, 0, 0, 0, 2, 1);
+        Ok(PartSolution::U32(product(slopes.iter().map(|slope| {
+            descent_and_go_right(input, 0, 0, 0, slope.down, slope.right)
+        }))?))
+    }
 
-        PartSolution::U32(result1 * result2 * result3 * result4 * result5)
+    fn implementations(&self) -> &'static [Implementation<Self>] {
+        &[
+            Implementation {
+                name: "iterator",
+                part: 1,
+                solve: part_1_iterator,
+            },
+            Implementation {
+                name: "iterator",
+                part: 2,
+                solve: part_2_iterator,
+            },
+        ]
     }
 }
 
@@ -60,23 +236,136 @@ mod tests {
     mod part_1 {
         use pretty_assertions::assert_eq;
 
-        use crate::day_03::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_03::{Solution, part_1_iterator};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(191), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(191),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn iterator() {
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(191),
+                part_1_iterator(
+                    &solution,
+                    &solution.parse(solution.embedded_input()).unwrap(),
+                    &Context::default(),
+                )
+                .unwrap()
+            );
+        }
+
+        #[test]
+        fn no_columns() {
+            let solution = Solution {};
+
+            assert_eq!(
+                solution.parse("\n\n\n").unwrap_err().to_string(),
+                "The map has no columns"
+            );
         }
     }
     mod part_2 {
+        use std::collections::BTreeMap;
+        use std::sync::Arc;
+
         use pretty_assertions::assert_eq;
 
-        use crate::day_03::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_03::{Slope, Slopes, Solution, part_2_iterator};
+        use crate::shared::{Cancellation, Context, Day as _, Explain, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(1_478_615_040), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(1_478_615_040),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn iterator() {
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(1_478_615_040),
+                part_2_iterator(
+                    &solution,
+                    &solution.parse(solution.embedded_input()).unwrap(),
+                    &Context::default(),
+                )
+                .unwrap()
+            );
+        }
+
+        #[test]
+        fn slopes() {
+            assert_eq!(
+                "1,1 3,1".parse::<Slopes>(),
+                Ok(Slopes(vec![
+                    Slope { right: 1, down: 1 },
+                    Slope { right: 3, down: 1 }
+                ]))
+            );
+            assert_eq!(
+                "1,1 3".parse::<Slopes>(),
+                Err("`3` is not a slope, expected `RIGHT,DOWN`".to_owned())
+            );
+            assert_eq!(
+                "1,0".parse::<Slopes>(),
+                Err("`1,0` never reaches the bottom".to_owned())
+            );
+            assert_eq!(
+                " \t".parse::<Slopes>(),
+                Err("There are no slopes".to_owned())
+            );
+        }
+
+        #[test]
+        fn too_many_trees() {
+            let solution = Solution {};
+
+            let context = Context::new(
+                Cancellation::default(),
+                Arc::new(BTreeMap::from([(
+                    "slopes".to_owned(),
+                    "3,1 3,1 3,1 3,1 3,1".to_owned(),
+                )])),
+                Explain::default(),
+            );
+
+            let input = solution.parse(solution.embedded_input()).unwrap();
+
+            assert_eq!(
+                solution.part_2(&input, &context).unwrap_err().to_string(),
+                "The product of the trees is too large"
+            );
+            assert_eq!(
+                part_2_iterator(&solution, &input, &context)
+                    .unwrap_err()
+                    .to_string(),
+                "The product of the trees is too large"
+            );
         }
     }
 }
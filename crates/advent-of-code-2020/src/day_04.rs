@@ -1,6 +1,12 @@
-use crate::shared::{Day, PartSolution};
+use color_eyre::eyre::{WrapErr as _, bail};
+use shared::parser::{
+    Cursor, ParseResult, either, groups, key_value, literal, newline, one_of, parse, separated, word,
+};
+
+use crate::shared::{Context, Day, Metadata, PartSolution};
+
 #[derive(Default, Debug)]
-struct Passport {
+pub struct Passport {
     byr: Option<String>,
     iyr: Option<String>,
     eyr: Option<String>,
@@ -143,15 +149,12 @@ impl Passport {
     }
 }
 
-fn parse_passport(passport_pieces: &[&str]) -> Passport {
-    let mut passport: Passport = Passport::default();
-
-    for piece in passport_pieces {
-        let split: Vec<&str> = piece.split(':').collect();
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
 
-        let identifier = split[0];
-        let value = split[1];
+fn parse_passport(fields: &[(&str, &str)]) -> Result<Passport, color_eyre::Report> {
+    let mut passport: Passport = Passport::default();
 
+    for &(identifier, value) in fields {
         match identifier {
             "byr" /*(Birth Year)*/ => passport.byr = Some(value.into()),
             "iyr" /*(Issue Year)*/ => passport.iyr = Some(value.into()),
@@ -161,66 +164,80 @@ fn parse_passport(passport_pieces: &[&str]) -> Passport {
             "ecl" /*(Eye Color)*/ => passport.ecl = Some(value.into()),
             "pid" /*(Passport ID)*/ => passport.pid = Some(value.into()),
             "cid" /*(Country ID)*/ => passport.cid = Some(value.into()),
-            _ => panic!("WTF DID YOU GIVE ME?")
+            _ => bail!("Unknown passport field `{}`", identifier)
         }
     }
 
-    passport
+    Ok(passport)
 }
 
-fn parse_line_group(line_group: &[String]) -> Passport {
-    let mut passport_pieces = Vec::new();
-
-    for line in line_group {
-        line.split(' ').for_each(|p| passport_pieces.push(p));
-    }
+/// The `<field>:<value>` pairs of a passport, separated by spaces or line breaks.
+fn passport_fields<'a>(cursor: &mut Cursor<'a>) -> ParseResult<Vec<(&'a str, &'a str)>> {
+    let field = key_value(one_of(&FIELDS), literal(":"), word);
 
-    parse_passport(&passport_pieces)
+    separated(field, either(literal(" "), newline))(cursor)
 }
 
-fn parse_lines_into_groups(lines: &[String]) -> Vec<Passport> {
-    let mut passports: Vec<Passport> = Vec::new();
+fn parse_passports(input: &str) -> Result<Vec<Passport>, color_eyre::Report> {
+    let groups = parse(input, groups(passport_fields))?;
 
-    let groups = lines.split(String::is_empty);
+    let mut passports: Vec<Passport> = Vec::new();
 
-    for group in groups {
-        let passport = parse_line_group(group);
+    for (index, fields) in groups.iter().enumerate() {
+        let passport = parse_passport(fields)
+            .wrap_err_with(|| format!("Failed to parse passport {}", index + 1))?;
 
         passports.push(passport);
     }
 
-    passports
+    Ok(passports)
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_04/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
-        let valid_passports = parse_lines_into_groups(&lines)
-            .into_iter()
-            .filter(Passport::is_passport_valid_part_1)
-            .count();
+    type Input = Vec<Passport>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 4,
+            title: "Passport Processing",
+        }
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_04/input.txt")
+    }
 
-        PartSolution::USize(valid_passports)
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        parse_passports(input)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_04/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let valid_passports = input
+            .iter()
+            .filter(|passport| passport.is_passport_valid_part_1())
+            .count();
+
+        Ok(PartSolution::USize(valid_passports))
+    }
 
-        let valid_passports = parse_lines_into_groups(&lines)
-            .into_iter()
-            .filter(Passport::is_passport_valid_part_2)
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let valid_passports = input
+            .iter()
+            .filter(|passport| passport.is_passport_valid_part_2())
             .count();
 
-        PartSolution::USize(valid_passports)
+        Ok(PartSolution::USize(valid_passports))
     }
 }
 
@@ -230,24 +247,49 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_04::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(200), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(200),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
     }
 
     #[cfg(test)]
     mod part_2 {
         use pretty_assertions::assert_eq;
+        use shared::parser::parse;
+
+        use crate::day_04::{Passport, Solution, parse_passport, passport_fields};
+        use crate::shared::{Context, Day as _, PartSolution};
 
-        use crate::day_04::{Passport, Solution, parse_line_group};
-        use crate::shared::{Day as _, PartSolution};
+        fn parse_line_group(line_group: &str) -> Result<Passport, color_eyre::Report> {
+            parse_passport(&parse(line_group, passport_fields)?)
+        }
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(116), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(116),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
@@ -379,10 +421,6 @@ mod tests {
             assert!(!passport.is_pid_valid());
         }
 
-        fn arr_of_amp_str_to_vec_of_string(arr: &[&str]) -> Vec<String> {
-            arr.iter().map(|s| (*s).to_owned()).collect()
-        }
-
         #[test]
         fn is_valid_passport_1() {
             let raw_lines = [
@@ -391,7 +429,8 @@ mod tests {
             ];
 
             assert!(
-                parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -404,7 +443,8 @@ mod tests {
             ];
 
             assert!(
-                parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -419,7 +459,8 @@ mod tests {
             ];
 
             assert!(
-                parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -430,7 +471,8 @@ mod tests {
                 ["iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719"];
 
             assert!(
-                parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -443,7 +485,8 @@ mod tests {
             ];
 
             assert!(
-                !parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                !parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -457,7 +500,8 @@ mod tests {
             ];
 
             assert!(
-                !parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                !parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -470,7 +514,8 @@ mod tests {
             ];
 
             assert!(
-                !parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                !parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -484,7 +529,8 @@ mod tests {
             ];
 
             assert!(
-                !parse_line_group(&arr_of_amp_str_to_vec_of_string(&raw_lines))
+                !parse_line_group(&raw_lines.join("\n"))
+                    .unwrap()
                     .is_passport_valid_part_2()
             );
         }
@@ -1,6 +1,8 @@
-use crate::shared::{Day, PartSolution};
+use color_eyre::eyre::{OptionExt as _, WrapErr as _, bail};
 
-fn parse_seat(seat_line: &str) -> (u32, u32) {
+use crate::shared::{Context, Day, Metadata, PartSolution};
+
+fn parse_seat(seat_line: &str) -> Result<(u32, u32), color_eyre::Report> {
     const LOWER_BITS_ROW: u32 = 0;
     const UPPER_BITS_ROW: u32 = LOWER_BITS_ROW + 6;
     const LOWER_BITS_COL: u32 = UPPER_BITS_ROW + 1;
@@ -12,70 +14,85 @@ fn parse_seat(seat_line: &str) -> (u32, u32) {
     let mut column: u32 = 0;
 
     for i in 0..=UPPER_BITS_ROW {
-        match pieces.next().unwrap() {
-            'F' => (),
-            'B' => row |= 0b1 << (UPPER_BITS_ROW - i),
-            _ => panic!("Not F or B"),
+        match pieces.next() {
+            Some('F') => (),
+            Some('B') => row |= 0b1 << (UPPER_BITS_ROW - i),
+            other => bail!("Expected F or B, got {:?}", other),
         }
     }
 
     for i in 7..=UPPER_BITS_COL {
-        match pieces.next().unwrap() {
-            'L' => (),
-            'R' => column |= 0b1 << (UPPER_BITS_COL - i),
-            _ => panic!("Not L or R"),
+        match pieces.next() {
+            Some('L') => (),
+            Some('R') => column |= 0b1 << (UPPER_BITS_COL - i),
+            other => bail!("Expected L or R, got {:?}", other),
         }
     }
 
-    (row, column)
+    Ok((row, column))
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_05/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
-        let max = lines
-            .iter()
-            .map(|l| parse_seat(l))
-            .map(|(r, c)| r * 8 + c)
-            .max()
-            .unwrap();
+    /// Seat IDs, sorted.
+    type Input = Vec<u32>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 5,
+            title: "Binary Boarding",
+        }
+    }
 
-        PartSolution::U32(max)
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_05/input.txt")
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_05/input.txt")
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        let mut seat_ids: Vec<u32> = input
             .lines()
-            .map(Into::into)
-            .collect();
+            .map(|line| {
+                parse_seat(line)
+                    .map(|(r, c)| r * 8 + c)
+                    .wrap_err_with(|| format!("Invalid seat `{}`", line))
+            })
+            .collect::<Result<_, _>>()?;
 
-        let mut items: Vec<u32> = lines
-            .iter()
-            .map(|l| parse_seat(l))
-            .map(|(r, c)| r * 8 + c)
-            .collect();
+        seat_ids.sort_unstable();
 
-        items.sort_unstable();
+        Ok(seat_ids)
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let max = *input.last().ok_or_eyre("There are no seats")?;
+
+        Ok(PartSolution::U32(max))
+    }
 
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
         let mut previous = None;
 
         // let's find the gap
-        for i in items {
+        for &i in input {
             match previous {
                 Some(p) if (i - p) > 1 => {
-                    return PartSolution::U32(p + 1);
+                    return Ok(PartSolution::U32(p + 1));
                 },
                 _ => previous = Some(i),
             }
         }
 
-        panic!("Couldn't find item")
+        bail!("There is no free seat between the occupied ones")
     }
 }
 
@@ -85,38 +102,48 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_05::{Solution, parse_seat};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(951), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(951),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn seat_id_1() {
             let seat = "FBFBBFFRLR";
 
-            assert_eq!(parse_seat(seat), (44, 5));
+            assert_eq!(parse_seat(seat).unwrap(), (44, 5));
         }
 
         #[test]
         fn seat_id_2() {
             let seat = "BFFFBBFRRR";
 
-            assert_eq!(parse_seat(seat), (70, 7));
+            assert_eq!(parse_seat(seat).unwrap(), (70, 7));
         }
 
         #[test]
         fn seat_id_3() {
             let seat = "FFFBBBFRRR";
 
-            assert_eq!(parse_seat(seat), (14, 7));
+            assert_eq!(parse_seat(seat).unwrap(), (14, 7));
         }
         #[test]
         fn seat_id_4() {
             let seat = "BBFFBBFRLL";
 
-            assert_eq!(parse_seat(seat), (102, 4));
+            assert_eq!(parse_seat(seat).unwrap(), (102, 4));
         }
     }
 
@@ -125,38 +152,48 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_05::{Solution, parse_seat};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(653), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(653),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn seat_id_1() {
             let seat = "FBFBBFFRLR";
 
-            assert_eq!(parse_seat(seat), (44, 5));
+            assert_eq!(parse_seat(seat).unwrap(), (44, 5));
         }
 
         #[test]
         fn seat_id_2() {
             let seat = "BFFFBBFRRR";
 
-            assert_eq!(parse_seat(seat), (70, 7));
+            assert_eq!(parse_seat(seat).unwrap(), (70, 7));
         }
 
         #[test]
         fn seat_id_3() {
             let seat = "FFFBBBFRRR";
 
-            assert_eq!(parse_seat(seat), (14, 7));
+            assert_eq!(parse_seat(seat).unwrap(), (14, 7));
         }
         #[test]
         fn seat_id_4() {
             let seat = "BBFFBBFRLL";
 
-            assert_eq!(parse_seat(seat), (102, 4));
+            assert_eq!(parse_seat(seat).unwrap(), (102, 4));
         }
     }
 }
@@ -1,6 +1,7 @@
+use color_eyre::eyre::{WrapErr as _, bail};
 use hashbrown::HashMap;
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{Context, Day, Metadata, PartSolution};
 
 fn parse_group_of_answers(group: &[String]) -> u32 {
     let mut answers: Vec<char> = Vec::new();
@@ -15,8 +16,7 @@ fn parse_group_of_answers(group: &[String]) -> u32 {
     answers.len().try_into().unwrap()
 }
 
-#[expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
-fn count_of_questions_answered_by_everybody(group: &[String]) -> u32 {
+fn count_of_questions_answered_by_everybody(group: &[String]) -> Result<u32, color_eyre::Report> {
     let mut count_of_answers: HashMap<char, u32> = HashMap::new();
 
     for line in group {
@@ -24,11 +24,9 @@ fn count_of_questions_answered_by_everybody(group: &[String]) -> u32 {
         duplicate_answer_per_line_check.sort_unstable();
         duplicate_answer_per_line_check.dedup();
 
-        assert_eq!(
-            duplicate_answer_per_line_check.len(),
-            line.len(),
-            "Length doesn't match duplicate answer"
-        );
+        if duplicate_answer_per_line_check.len() != line.len() {
+            bail!("`{}` answers the same question more than once", line);
+        }
 
         for c in duplicate_answer_per_line_check {
             let count = *(count_of_answers.get(&c).unwrap_or(&0));
@@ -38,7 +36,7 @@ fn count_of_questions_answered_by_everybody(group: &[String]) -> u32 {
     }
 
     let mut total_answers_that_everybody_answered: u32 = 0;
-    let people_in_group = group.len().try_into().unwrap();
+    let people_in_group: u32 = group.len().try_into().unwrap();
 
     for (_, value) in count_of_answers {
         if value == people_in_group {
@@ -46,48 +44,67 @@ fn count_of_questions_answered_by_everybody(group: &[String]) -> u32 {
         }
     }
 
-    total_answers_that_everybody_answered
+    Ok(total_answers_that_everybody_answered)
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_06/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    /// The answers of each group, one line per person.
+    type Input = Vec<Vec<String>>;
 
-        let mut all_answers: Vec<u32> = Vec::new();
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 6,
+            title: "Custom Customs",
+        }
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_06/input.txt")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        let lines: Vec<String> = input.lines().map(Into::into).collect();
 
-        let groups = lines.split(String::is_empty);
+        Ok(lines
+            .split(String::is_empty)
+            .map(<[String]>::to_vec)
+            .collect())
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let mut all_answers: Vec<u32> = Vec::new();
 
-        for group in groups {
+        for group in input {
             let unique_answers_in_group = parse_group_of_answers(group);
 
             all_answers.push(unique_answers_in_group);
         }
 
-        PartSolution::U32(all_answers.iter().sum::<u32>())
+        Ok(PartSolution::U32(all_answers.iter().sum::<u32>()))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_06/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
         let mut all_answers: Vec<u32> = Vec::new();
 
-        let groups = lines.split(String::is_empty);
-
-        for group in groups {
-            let unique_answers_in_group = count_of_questions_answered_by_everybody(group);
+        for group in input {
+            let unique_answers_in_group = count_of_questions_answered_by_everybody(group)
+                .wrap_err_with(|| format!("Invalid answers in group {:?}", group))?;
 
             all_answers.push(unique_answers_in_group);
         }
 
-        PartSolution::U32(all_answers.iter().sum::<u32>())
+        Ok(PartSolution::U32(all_answers.iter().sum::<u32>()))
     }
 }
 
@@ -97,11 +114,21 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_06::{Solution, parse_group_of_answers};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(6521), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(6521),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
@@ -149,32 +176,51 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_06::{Solution, count_of_questions_answered_by_everybody};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(3305), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(3305),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn answer_set_1() {
             let answer_set = ["abc".to_owned()];
 
-            assert_eq!(count_of_questions_answered_by_everybody(&answer_set), 3);
+            assert_eq!(
+                count_of_questions_answered_by_everybody(&answer_set).unwrap(),
+                3
+            );
         }
 
         #[test]
         fn answer_set_2() {
             let answer_set = ["a".to_owned(), "b".to_owned(), "c".to_owned()];
 
-            assert_eq!(count_of_questions_answered_by_everybody(&answer_set), 0);
+            assert_eq!(
+                count_of_questions_answered_by_everybody(&answer_set).unwrap(),
+                0
+            );
         }
 
         #[test]
         fn answer_set_3() {
             let answer_set = ["ab".to_owned(), "ac".to_owned()];
 
-            assert_eq!(count_of_questions_answered_by_everybody(&answer_set), 1);
+            assert_eq!(
+                count_of_questions_answered_by_everybody(&answer_set).unwrap(),
+                1
+            );
         }
 
         #[test]
@@ -186,14 +232,20 @@ mod tests {
                 "a".to_owned(),
             ];
 
-            assert_eq!(count_of_questions_answered_by_everybody(&answer_set), 1);
+            assert_eq!(
+                count_of_questions_answered_by_everybody(&answer_set).unwrap(),
+                1
+            );
         }
 
         #[test]
         fn answer_set_5() {
             let answer_set = ["b".to_owned()];
 
-            assert_eq!(count_of_questions_answered_by_everybody(&answer_set), 1);
+            assert_eq!(
+                count_of_questions_answered_by_everybody(&answer_set).unwrap(),
+                1
+            );
         }
     }
 }
@@ -1,179 +1,141 @@
-use core::fmt;
-use std::cell::RefCell;
-use std::rc::Rc;
+use color_eyre::eyre::eyre;
+use shared::graph::{Direction, Graph, NodeId};
+use shared::parser::{
+    Cursor, ParseResult, either, identifier, integer, lines, literal, map, one_of, parse, separated,
+};
 
-use hashbrown::HashMap;
+use crate::shared::{Context, Day, Metadata, Param, PartSolution};
 
-use crate::shared::{Day, PartSolution};
+/// A color, two words like `shiny gold`.
+fn color(cursor: &mut Cursor<'_>) -> ParseResult<String> {
+    let shade = identifier(cursor)?;
+    literal(" ")(cursor)?;
+    let hue = identifier(cursor)?;
 
-#[derive(Default, Debug)]
-pub struct Bag {
-    pub name: String,
-    pub parents: RefCell<Vec<Rc<Bag>>>,
-    pub children: RefCell<Vec<(u32, Rc<Bag>)>>,
+    Ok(format!("{} {}", shade, hue))
 }
 
-impl fmt::Display for Bag {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let child_names: Vec<String> = self
-            .children
-            .borrow()
-            .iter()
-            .map(|&(ref count, ref bag)| format!("Name: {}, count: {}", bag.name, count))
-            .collect();
-
-        let parent_names: Vec<String> = self
-            .parents
-            .borrow()
-            .iter()
-            .map(|bag| format!("Name: {}", bag.name))
-            .collect();
-
-        write!(
-            f,
-            "Name: {}, parents: {:?}, children: {:?} ",
-            self.name, parent_names, child_names
-        )
-    }
-}
-
-pub fn map_bag_color_with_count(bag_color_with_count: &str) -> (u32, String) {
-    let split = bag_color_with_count.trim().split_once(' ').unwrap();
+/// `<count> <color> bag`, or `bags` when there are more.
+fn bag_with_count(cursor: &mut Cursor<'_>) -> ParseResult<(u32, String)> {
+    let count = integer(cursor)?;
+    literal(" ")(cursor)?;
+    let color = color(cursor)?;
+    one_of(&[" bags", " bag"])(cursor)?;
 
-    let _test: Vec<char> = bag_color_with_count.chars().collect();
-
-    (split.0.parse().unwrap(), split.1.to_owned())
+    Ok((count, color))
 }
 
-pub fn parse_bag_line(bag_line: &str) -> (String, Vec<(u32, String)>) {
-    let cleaned_up = bag_line
-        .replace("bags", "")
-        .replace("bag", "")
-        .replace('.', "");
-
-    let split: Vec<&str> = cleaned_up.split("contain").collect();
-
-    let bag_name = split.first().unwrap().trim();
-
-    let inside_bags = split.get(1).unwrap().trim();
-
-    if inside_bags == "no other" {
-        return (bag_name.to_owned(), Vec::<(u32, String)>::new());
-    }
-
-    let inside_bags_with_count = inside_bags
-        .split(',')
-        .map(map_bag_color_with_count)
-        .collect();
-
-    (bag_name.to_owned(), inside_bags_with_count)
+pub fn parse_bag_line(cursor: &mut Cursor<'_>) -> ParseResult<(String, Vec<(u32, String)>)> {
+    // grammar:
+    // <color> bags contain <count> <color> bag(s), ... .
+    // <color> bags contain no other bags.
+    let bag_name = color(cursor)?;
+    literal(" bags contain ")(cursor)?;
+    let inside_bags_with_count = either(
+        map(literal("no other bags"), |_| Vec::new()),
+        separated(bag_with_count, literal(", ")),
+    )(cursor)?;
+    literal(".")(cursor)?;
+
+    Ok((bag_name, inside_bags_with_count))
 }
 
-pub fn parse_bags(bag_lines: &[String]) -> HashMap<String, Rc<Bag>> {
-    let mut bag_parsed: HashMap<String, Rc<Bag>> = HashMap::new();
-
-    for bag_line in bag_lines {
-        let (bag_name, count_with_bag_name) = parse_bag_line(bag_line);
-
-        let mut parsed_child_bags_current_line: Vec<(u32, Rc<Bag>)> = Vec::new();
-
-        for (count, child_bag_name) in count_with_bag_name {
-            let bag = bag_parsed.entry(child_bag_name.clone()).or_insert_with(|| {
-                Rc::new(Bag {
-                    name: child_bag_name.clone(),
-                    ..Bag::default()
-                })
-            });
-
-            parsed_child_bags_current_line.push((count, Rc::clone(bag)));
-        }
-
-        let bag: &Rc<Bag> = bag_parsed.entry(bag_name.clone()).or_insert_with(|| {
-            Rc::new(Bag {
-                name: bag_name.clone(),
-                ..Bag::default()
-            })
-        });
-
-        let mut bag_children = bag.children.borrow_mut();
+/// Links the bags from the parsed lines, each bag has an edge to the bags it contains weighted by how many it contains.
+pub fn parse_bags(bag_lines: &[(String, Vec<(u32, String)>)]) -> Graph<u32> {
+    let mut bags = Graph::new();
 
-        for (count, child_bag) in parsed_child_bags_current_line {
-            let mut child_bag_parents = child_bag.parents.borrow_mut();
+    for &(ref bag_name, ref count_with_bag_name) in bag_lines {
+        let bag = bags.intern(bag_name);
 
-            child_bag_parents.push(Rc::clone(bag));
+        for &(count, ref child_bag_name) in count_with_bag_name {
+            let child_bag = bags.intern(child_bag_name);
 
-            bag_children.push((count, Rc::clone(&child_bag)));
+            bags.add_edge(bag, child_bag, count);
         }
     }
 
-    bag_parsed
+    bags
 }
 
-fn get_parent_names_recursive(bag: &Rc<Bag>) -> Vec<String> {
-    let mut my_parent_names: Vec<String> = bag
-        .parents
-        .borrow()
-        .iter()
-        .map(|b| b.name.clone())
-        .collect();
-
-    bag.parents
-        .borrow()
-        .iter()
-        .for_each(|p| my_parent_names.append(&mut get_parent_names_recursive(p)));
-
-    my_parent_names
+fn find_bag(bags: &Graph<u32>, name: &str) -> Result<NodeId, color_eyre::Report> {
+    bags.node(name)
+        .ok_or_else(|| eyre!("There is no {} bag", name))
 }
 
-fn count_parents(bag_parsed: &HashMap<String, Rc<Bag>>, start: &str) -> u32 {
-    let start_bag = bag_parsed.get(start).unwrap();
-
-    let mut parent_names = get_parent_names_recursive(start_bag);
+fn count_parents(bags: &Graph<u32>, start: &str) -> Result<u32, color_eyre::Report> {
+    let start_bag = find_bag(bags, start)?;
 
-    parent_names.sort();
-    parent_names.dedup();
+    // every bag that ends up holding the start bag, apart from the start bag itself
+    let parents = bags.bfs(start_bag, Direction::Incoming).len() - 1;
 
-    u32::try_from(parent_names.len()).unwrap()
+    Ok(u32::try_from(parents)?)
 }
 
-fn count_bags_recursive(bag: &Rc<Bag>) -> u32 {
-    let children = bag.children.borrow();
+fn count_bags_recursive(bags: &Graph<u32>, start: &str) -> Result<u32, color_eyre::Report> {
+    let start_bag = find_bag(bags, start)?;
 
-    children
-        .iter()
-        .map(|&(c, ref b)| {
-            let sum_of_children = count_bags_recursive(b);
-
-            c + c * sum_of_children
-        })
-        .sum()
+    bags.fold(start_bag, |_, children| {
+        children
+            .iter()
+            .map(|&(&count, &sum_of_children)| count + count * sum_of_children)
+            .sum()
+    })
+    .map_err(|cycle| {
+        let names: Vec<&str> = cycle.nodes().iter().map(|&bag| bags.name(bag)).collect();
+
+        eyre!("The {} bags contain each other", names.join(", "))
+    })
 }
 
+const BAG: Param = Param {
+    name: "bag",
+    default: "shiny gold",
+    description: "The bag to count the containers and contents of.",
+};
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        const BAG_NAME: &str = "shiny gold";
-        let lines: Vec<String> = include_str!("day_07/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    type Input = Graph<u32>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 7,
+            title: "Handy Haversacks",
+        }
+    }
 
-        let bags = parse_bags(&lines);
+    fn params(&self) -> &'static [Param] {
+        &[BAG]
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_07/input.txt")
+    }
 
-        PartSolution::U32(count_parents(&bags, BAG_NAME))
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        Ok(parse_bags(&parse(input, lines(parse_bag_line))?))
     }
 
-    fn part_2(&self) -> PartSolution {
-        const BAG_NAME: &str = "shiny gold";
-        let lines: Vec<String> = include_str!("day_07/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let bag_name: String = context.param(&BAG)?;
 
-        let bags = parse_bags(&lines);
+        Ok(PartSolution::U32(count_parents(input, &bag_name)?))
+    }
+
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let bag_name: String = context.param(&BAG)?;
 
-        PartSolution::U32(count_bags_recursive(bags.get(BAG_NAME).unwrap()))
+        Ok(PartSolution::U32(count_bags_recursive(input, &bag_name)?))
     }
 }
 
@@ -182,20 +144,31 @@ mod tests {
 
     mod part_1 {
         use pretty_assertions::assert_eq;
+        use shared::parser::parse;
 
         use crate::day_07::{Solution, count_parents, parse_bag_line, parse_bags};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(272), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(272),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn parse_bag_line_1() {
             let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -213,7 +186,7 @@ mod tests {
         fn parse_bag_line_2() {
             let input = "dark orange bags contain 3 bright white bags, 4 muted yellow bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -231,7 +204,7 @@ mod tests {
         fn parse_bag_line_3() {
             let input = "bright white bags contain 1 shiny gold bag.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -246,7 +219,7 @@ mod tests {
         fn parse_bag_line_4() {
             let input = "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -261,7 +234,7 @@ mod tests {
         fn parse_bag_line_5() {
             let input = "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -276,7 +249,7 @@ mod tests {
         fn parse_bag_line_6() {
             let input = "dark olive bags contain 3 faded blue bags, 4 dotted black bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -291,7 +264,7 @@ mod tests {
         fn parse_bag_line_7() {
             let input = "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -306,7 +279,7 @@ mod tests {
         fn parse_bag_line_8() {
             let input = "faded blue bags contain no other bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(result, ("faded blue".to_owned(), vec![]));
         }
@@ -315,7 +288,7 @@ mod tests {
         fn parse_bag_line_9() {
             let input = "dotted black bags contain no other bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(result, ("dotted black".to_owned(), vec![]));
         }
@@ -334,9 +307,9 @@ mod tests {
                 "dotted black bags contain no other bags.",
             ];
 
-            let lines: Vec<String> = input.map(Into::into).into();
+            let lines: Vec<_> = input.map(|line| parse(line, parse_bag_line).unwrap()).into();
 
-            let rst = count_parents(&parse_bags(&lines), "shiny gold");
+            let rst = count_parents(&parse_bags(&lines), "shiny gold").unwrap();
 
             assert_eq!(rst, 4);
         }
@@ -344,20 +317,33 @@ mod tests {
 
     mod part_2 {
         use pretty_assertions::assert_eq;
+        use shared::parser::parse;
 
-        use crate::day_07::{Solution, count_bags_recursive, parse_bag_line, parse_bags};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_07::{
+            Solution, count_bags_recursive, count_parents, parse_bag_line, parse_bags,
+        };
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(172_246), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(172_246),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn parse_bag_line_1() {
             let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -375,7 +361,7 @@ mod tests {
         fn parse_bag_line_2() {
             let input = "dark orange bags contain 3 bright white bags, 4 muted yellow bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -393,7 +379,7 @@ mod tests {
         fn parse_bag_line_3() {
             let input = "bright white bags contain 1 shiny gold bag.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -408,7 +394,7 @@ mod tests {
         fn parse_bag_line_4() {
             let input = "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -423,7 +409,7 @@ mod tests {
         fn parse_bag_line_5() {
             let input = "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -438,7 +424,7 @@ mod tests {
         fn parse_bag_line_6() {
             let input = "dark olive bags contain 3 faded blue bags, 4 dotted black bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -453,7 +439,7 @@ mod tests {
         fn parse_bag_line_7() {
             let input = "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(
                 result,
@@ -468,7 +454,7 @@ mod tests {
         fn parse_bag_line_8() {
             let input = "faded blue bags contain no other bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(result, ("faded blue".to_owned(), vec![]));
         }
@@ -477,7 +463,7 @@ mod tests {
         fn parse_bag_line_9() {
             let input = "dotted black bags contain no other bags.";
 
-            let result = parse_bag_line(input);
+            let result = parse(input, parse_bag_line).unwrap();
 
             assert_eq!(result, ("dotted black".to_owned(), vec![]));
         }
@@ -496,13 +482,35 @@ mod tests {
                 "dark violet bags contain no other bags.",
             ];
 
-            let lines: Vec<String> = input.map(Into::into).into();
+            let lines: Vec<_> = input.map(|line| parse(line, parse_bag_line).unwrap()).into();
 
             let bags = parse_bags(&lines);
 
-            let rst = count_bags_recursive(bags.get(BAG_NAME).unwrap());
+            let rst = count_bags_recursive(&bags, BAG_NAME).unwrap();
 
             assert_eq!(rst, 126);
         }
+
+        #[test]
+        fn bags_containing_each_other() {
+            let solution = Solution {};
+
+            let bags = solution
+                .parse(
+                    "shiny gold bags contain 1 dark red bag.\n\
+                     dark red bags contain 2 dark orange bags.\n\
+                     dark orange bags contain 3 shiny gold bags, 1 faded blue bag.\n\
+                     faded blue bags contain no other bags.",
+                )
+                .unwrap();
+
+            assert_eq!(
+                count_bags_recursive(&bags, "shiny gold")
+                    .unwrap_err()
+                    .to_string(),
+                "The shiny gold, dark red, dark orange bags contain each other"
+            );
+            assert_eq!(count_parents(&bags, "faded blue").unwrap(), 3);
+        }
     }
 }
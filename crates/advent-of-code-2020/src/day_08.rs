@@ -1,6 +1,10 @@
+use color_eyre::eyre::bail;
 use hashbrown::HashSet;
+use shared::parser::{
+    ParseError, identifier, integer, key_value, lines, literal, parse, try_map,
+};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{Context, Day, Metadata, Note, PartSolution};
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Operation {
@@ -9,27 +13,32 @@ pub enum Operation {
     Nop(isize),
 }
 
-fn map_operation(operation: &str, argument: isize) -> Operation {
-    match operation {
-        "acc" => Operation::Acc(argument),
-        "jmp" => Operation::Jmp(argument),
-        "nop" => Operation::Nop(argument),
-        _ => panic!("Yea, no"),
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Operation::Acc(argument) => write!(f, "acc {:+}", argument),
+            Operation::Jmp(argument) => write!(f, "jmp {:+}", argument),
+            Operation::Nop(argument) => write!(f, "nop {:+}", argument),
+        }
     }
 }
 
-pub fn parse_lines(lines: &[String]) -> Vec<Operation> {
-    let mut instructions = Vec::new();
-    for line in lines {
-        let split: Vec<&str> = line.split(' ').collect();
-
-        let argument = split[1].parse::<isize>().unwrap();
-        let operation = map_operation(split[0], argument);
-
-        instructions.push(operation);
+fn map_operation((operation, argument): (&str, isize)) -> Result<Operation, String> {
+    match operation {
+        "acc" => Ok(Operation::Acc(argument)),
+        "jmp" => Ok(Operation::Jmp(argument)),
+        "nop" => Ok(Operation::Nop(argument)),
+        _ => Err("`acc`, `jmp` or `nop`".to_owned()),
     }
+}
 
-    instructions
+pub fn parse_lines(input: &str) -> Result<Vec<Operation>, ParseError> {
+    let instruction = try_map(
+        key_value(identifier, literal(" "), integer),
+        map_operation,
+    );
+
+    parse(input, lines(instruction))
 }
 
 #[derive(PartialEq, Debug)]
@@ -94,30 +103,44 @@ fn build_new_vector(operations: &[Operation], to_swap_index: usize) -> Vec<Opera
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_08/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
-        let operations = parse_lines(&lines);
+    type Input = Vec<Operation>;
 
-        if let Ended::EndlessLoop(acc) = execute_until_same_line_reached(&operations) {
-            PartSolution::ISize(acc)
-        } else {
-            panic!("Application ended")
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 8,
+            title: "Handheld Halting",
         }
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_08/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_08/input.txt")
+    }
 
-        let operations = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        Ok(parse_lines(input)?)
+    }
 
-        let to_swap: Vec<usize> = operations
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        match execute_until_same_line_reached(input) {
+            Ended::EndlessLoop(acc) => Ok(PartSolution::ISize(acc)),
+            Ended::TheEnd(acc) => bail!(
+                "The program ended instead of looping, with {} in the accumulator",
+                acc
+            ),
+        }
+    }
+
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let to_swap: Vec<usize> = input
             .iter()
             .enumerate()
             .filter_map(|(index, f)| match *f {
@@ -127,15 +150,25 @@ impl Day for Solution {
             .collect();
 
         for to_swap_index in to_swap {
-            let beginning = build_new_vector(&operations, to_swap_index);
+            let beginning = build_new_vector(input, to_swap_index);
 
             match execute_until_same_line_reached(&beginning) {
-                Ended::TheEnd(acc) => return PartSolution::ISize(acc),
+                Ended::TheEnd(acc) => {
+                    context.explain().note(|| {
+                        Note::new("Flipped the instruction that makes the program end")
+                            .with("line", to_swap_index + 1)
+                            .with("from", input[to_swap_index])
+                            .with("to", beginning[to_swap_index])
+                            .with("accumulator", acc)
+                    });
+
+                    return Ok(PartSolution::ISize(acc));
+                },
                 Ended::EndlessLoop(_) => {},
             }
         }
 
-        panic!("No non-terminating combination found");
+        bail!("No single swap of a jmp or nop makes the program end");
     }
 }
 
@@ -148,69 +181,96 @@ mod tests {
         use crate::day_08::{
             Ended, Operation, Solution, execute_until_same_line_reached, parse_lines,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
+
+        #[test]
+        fn unknown_operation() {
+            let solution = Solution {};
+
+            let error = solution.parse("nop +0\nmul +3").unwrap_err();
+
+            assert_eq!(
+                format!("{:#}", error),
+                "Line 2, column 1: expected `acc`, `jmp` or `nop`, found `mul +3`"
+            );
+        }
+
+        #[test]
+        fn program_ends() {
+            let solution = Solution {};
+
+            let error = solution
+                .part_1(
+                    &solution.parse("nop +0\nacc +3").unwrap(),
+                    &Context::default(),
+                )
+                .unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                "The program ended instead of looping, with 3 in the accumulator"
+            );
+        }
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::ISize(1584), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::ISize(1584),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn nop_positive() {
-            let input = vec!["nop +1".to_owned()];
-
             let expected = Operation::Nop(1);
-            let parsed = parse_lines(&input);
+            let parsed = parse_lines("nop +1").unwrap();
 
             assert_eq!(parsed[0], expected);
         }
 
         #[test]
         fn nop_negative() {
-            let input = vec!["nop -20".to_owned()];
-
             let expected = Operation::Nop(-20);
-            let parsed = parse_lines(&input);
+            let parsed = parse_lines("nop -20").unwrap();
 
             assert_eq!(parsed[0], expected);
         }
 
         #[test]
         fn jmp_positive() {
-            let input = vec!["jmp +3".to_owned()];
-
             let expected = Operation::Jmp(3);
-            let parsed = parse_lines(&input);
+            let parsed = parse_lines("jmp +3").unwrap();
 
             assert_eq!(parsed[0], expected);
         }
 
         #[test]
         fn jmp_negative() {
-            let input = vec!["jmp -4".to_owned()];
-
             let expected = Operation::Jmp(-4);
-            let parsed = parse_lines(&input);
+            let parsed = parse_lines("jmp -4").unwrap();
 
             assert_eq!(parsed[0], expected);
         }
 
         #[test]
         fn acc_positive() {
-            let input = vec!["acc +5".to_owned()];
-
             let expected = Operation::Acc(5);
-            let parsed = parse_lines(&input);
+            let parsed = parse_lines("acc +5").unwrap();
 
             assert_eq!(parsed[0], expected);
         }
 
         #[test]
         fn acc_negative() {
-            let input = vec!["acc -6".to_owned()];
-
             let expected = Operation::Acc(-6);
-            let parsed = parse_lines(&input);
+            let parsed = parse_lines("acc -6").unwrap();
 
             assert_eq!(parsed[0], expected);
         }
@@ -230,15 +290,13 @@ mod tests {
 
         #[test]
         fn example() {
-            let input: Vec<String> = [
+            let input = [
                 "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
                 "acc +6",
             ]
-            .iter()
-            .map(|s| (*s).to_owned())
-            .collect();
+            .join("\n");
 
-            let operations = parse_lines(&input);
+            let operations = parse_lines(&input).unwrap();
 
             let acc = execute_until_same_line_reached(&operations);
 
@@ -247,29 +305,39 @@ mod tests {
     }
 
     mod part_2 {
+        use std::sync::Arc;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_08::{
             Ended, Solution, build_new_vector, execute_until_same_line_reached, parse_lines,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Cancellation, Context, Day as _, Explain, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::ISize(920), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::ISize(920),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn sample_data() {
-            let input: Vec<String> = [
+            let input = [
                 "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
                 "acc +6",
             ]
-            .iter()
-            .map(|s| (*s).to_owned())
-            .collect();
+            .join("\n");
 
-            let operations = parse_lines(&input);
+            let operations = parse_lines(&input).unwrap();
 
             let new_vector = build_new_vector(&operations, 7);
 
@@ -278,6 +346,37 @@ mod tests {
             assert_eq!(Ended::TheEnd(8), acc);
         }
 
+        #[test]
+        fn explain() {
+            let solution = Solution {};
+
+            let context = Context::new(Cancellation::default(), Arc::default(), Explain::enabled());
+
+            let answer = solution
+                .part_2(
+                    &solution
+                        .parse("nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6")
+                        .unwrap(),
+                    &context,
+                )
+                .unwrap();
+
+            assert_eq!(answer, PartSolution::ISize(8));
+
+            let notes: Vec<String> = context
+                .into_notes()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+
+            assert_eq!(
+                notes,
+                vec![
+                    "Flipped the instruction that makes the program end: line = 8, from = jmp -4, to = nop -4, accumulator = 8"
+                ]
+            );
+        }
+
         #[test]
         fn pieces() {
             const SPLIT_AT: usize = 5;
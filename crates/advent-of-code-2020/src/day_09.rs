@@ -1,6 +1,9 @@
+use std::num::NonZeroUsize;
+
+use color_eyre::eyre::{WrapErr as _, bail};
 use hashbrown::HashMap;
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{Context, Day, Metadata, Note, Param, PartSolution};
 
 fn find_sum_of_2_is(sum: u64, rest: &[u64]) -> Result<(), String> {
     let mut missing_to_value: HashMap<u64, u64> = HashMap::new();
@@ -26,27 +29,46 @@ fn find_sum_of_2_is(sum: u64, rest: &[u64]) -> Result<(), String> {
     ))
 }
 
-fn slide_until_sum_of_any_2_in_last_x_is_not_current_value<const N: usize>(input: &[u64]) -> u64 {
+fn slide_until_sum_of_any_2_in_last_x_is_not_current_value(
+    input: &[u64],
+    preamble: NonZeroUsize,
+) -> Result<u64, color_eyre::Report> {
     let mut offset = 0;
 
-    for to_test in input.array_windows::<N>() {
-        let target_sum = *input.get(offset + N).unwrap();
+    for to_test in input.windows(preamble.get()) {
+        let Some(&target_sum) = input.get(offset + preamble.get()) else {
+            break;
+        };
 
         if find_sum_of_2_is(target_sum, to_test).is_ok() {
             offset += 1;
         } else {
-            return target_sum;
+            return Ok(target_sum);
         }
     }
 
-    panic!("Shouldn't get here");
+    bail!(
+        "Every number is the sum of 2 of the {} numbers before it",
+        preamble
+    );
 }
 
-fn find_contiguous_set_of_numbers_that_sum_up_to(input: &[u64], target: u64) -> (u64, u64) {
+fn find_contiguous_set_of_numbers_that_sum_up_to(
+    input: &[u64],
+    target: u64,
+    context: &Context,
+) -> Result<(u64, u64), color_eyre::Report> {
     let mut offset = 0;
     let mut to_take = 2;
 
     loop {
+        context.cancellation().check()?;
+
+        // the numbers are positive, if everything up to the end is too little, starting later won't help
+        if offset + to_take > input.len() {
+            bail!("No contiguous set of at least 2 numbers sums up to {}", target);
+        }
+
         let range: Vec<&u64> = input.iter().skip(offset).take(to_take).collect();
 
         let sum: u64 = range.iter().fold(0, |acc, current| acc + **current);
@@ -59,7 +81,16 @@ fn find_contiguous_set_of_numbers_that_sum_up_to(input: &[u64], target: u64) ->
             std::cmp::Ordering::Equal => {
                 let min = *range.iter().min().unwrap();
                 let max = *range.iter().max().unwrap();
-                return (*min, *max);
+
+                context.explain().note(|| {
+                    Note::new("Found a contiguous range that sums up to the invalid number")
+                        .with("target", target)
+                        .with("lines", format!("{} to {}", offset + 1, offset + to_take))
+                        .with("smallest", min)
+                        .with("largest", max)
+                });
+
+                return Ok((*min, *max));
             },
             std::cmp::Ordering::Greater => {
                 to_take += 1;
@@ -68,32 +99,67 @@ fn find_contiguous_set_of_numbers_that_sum_up_to(input: &[u64], target: u64) ->
     }
 }
 
+const PREAMBLE: Param = Param {
+    name: "preamble",
+    default: "25",
+    description: "How many numbers before each number it needs to be the sum of 2 of.",
+};
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_09/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-        let input: Vec<u64> = lines.iter().map(|s| s.parse::<u64>().unwrap()).collect();
+    type Input = Vec<u64>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 9,
+            title: "Encoding Error",
+        }
+    }
 
-        let solution = slide_until_sum_of_any_2_in_last_x_is_not_current_value::<25>(&input);
+    fn params(&self) -> &'static [Param] {
+        &[PREAMBLE]
+    }
 
-        PartSolution::U64(solution)
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_09/input.txt")
     }
 
-    fn part_2(&self) -> PartSolution {
-        const TARGET: u64 = 138_879_426; // from day 9 part 1;
-        let lines: Vec<String> = include_str!("day_09/input.txt")
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        input
             .lines()
-            .map(Into::into)
-            .collect();
-        let input: Vec<u64> = lines.iter().map(|s| s.parse::<u64>().unwrap()).collect();
+            .map(|s| {
+                s.parse::<u64>()
+                    .wrap_err_with(|| format!("`{}` is not a number", s))
+            })
+            .collect()
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let solution =
+            slide_until_sum_of_any_2_in_last_x_is_not_current_value(input, context.param(&PREAMBLE)?)?;
+
+        Ok(PartSolution::U64(solution))
+    }
 
-        let (lowest, highest) = find_contiguous_set_of_numbers_that_sum_up_to(&input, TARGET);
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        // the number part 1 finds
+        let target =
+            slide_until_sum_of_any_2_in_last_x_is_not_current_value(input, context.param(&PREAMBLE)?)?;
 
-        PartSolution::U64(lowest + highest)
+        let (lowest, highest) =
+            find_contiguous_set_of_numbers_that_sum_up_to(input, target, context)?;
+
+        Ok(PartSolution::U64(lowest + highest))
     }
 }
 
@@ -101,14 +167,26 @@ impl Day for Solution {
 mod tests {
 
     mod part_1 {
+        use std::num::NonZeroUsize;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_09::{Solution, slide_until_sum_of_any_2_in_last_x_is_not_current_value};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U64(138_879_426), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U64(138_879_426),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
@@ -121,21 +199,36 @@ mod tests {
             .map(|s| s.parse::<u64>().unwrap())
             .collect();
 
-            let value = slide_until_sum_of_any_2_in_last_x_is_not_current_value::<5>(&input);
+            let value =
+                slide_until_sum_of_any_2_in_last_x_is_not_current_value(&input, NonZeroUsize::new(5).unwrap())
+                    .unwrap();
 
             assert_eq!(127, value);
         }
     }
 
     mod part_2 {
+        use std::collections::BTreeMap;
+        use std::sync::Arc;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_09::{Solution, find_contiguous_set_of_numbers_that_sum_up_to};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Cancellation, Context, Day as _, Explain, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U64(23_761_694), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U64(23_761_694),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
@@ -148,10 +241,43 @@ mod tests {
             .map(|s| s.parse::<u64>().unwrap())
             .collect();
 
-            let (lowest, highest) = find_contiguous_set_of_numbers_that_sum_up_to(&input, 127);
+            let (lowest, highest) = find_contiguous_set_of_numbers_that_sum_up_to(&input, 127, &Context::default()).unwrap();
 
             assert_eq!(15, lowest);
             assert_eq!(47, highest);
         }
+
+        #[test]
+        fn example_with_preamble() {
+            let solution = Solution {};
+
+            let context = Context::new(
+                Cancellation::default(),
+                Arc::new(BTreeMap::from([("preamble".to_owned(), "5".to_owned())])),
+                Explain::enabled(),
+            );
+
+            let input = solution
+                .parse("35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n219\n299\n277\n309\n576")
+                .unwrap();
+
+            assert_eq!(
+                PartSolution::U64(62),
+                solution.part_2(&input, &context).unwrap()
+            );
+
+            let notes: Vec<String> = context
+                .into_notes()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+
+            assert_eq!(
+                notes,
+                vec![
+                    "Found a contiguous range that sums up to the invalid number: target = 127, lines = 3 to 6, smallest = 15, largest = 47"
+                ]
+            );
+        }
     }
 }
@@ -1,17 +1,18 @@
+use color_eyre::eyre::{OptionExt as _, WrapErr as _, bail};
 use hashbrown::HashMap;
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{Context, Day, Metadata, PartSolution};
 
-fn calculate_possibilities(mut input: Vec<u32>) -> u64 {
+fn calculate_possibilities(mut input: Vec<u32>) -> Result<u64, color_eyre::Report> {
     input.sort_unstable();
 
-    input.push(input.last().map(|x| x + 3).unwrap());
+    input.push(input.last().map(|x| x + 3).ok_or_eyre("There are no adapters")?);
 
     let mut start_with_0 = Vec::with_capacity(input.len() + 1);
     start_with_0.push(0);
     start_with_0.append(&mut input);
 
-    paths_to_end(&mut HashMap::new(), &start_with_0, 0)
+    Ok(paths_to_end(&mut HashMap::new(), &start_with_0, 0))
 }
 
 fn paths_to_end(cache: &mut HashMap<usize, u64>, adapters: &[u32], current: usize) -> u64 {
@@ -41,7 +42,7 @@ fn paths_to_end(cache: &mut HashMap<usize, u64>, adapters: &[u32], current: usiz
     sum
 }
 
-fn calculate_step_up_differences(mut input: Vec<u32>) -> (u32, u32, u32) {
+fn calculate_step_up_differences(mut input: Vec<u32>) -> Result<(u32, u32, u32), color_eyre::Report> {
     let mut stepup_1 = 0;
     let mut stepup_2 = 0;
     let mut stepup_3 = 0;
@@ -57,14 +58,19 @@ fn calculate_step_up_differences(mut input: Vec<u32>) -> (u32, u32, u32) {
                     1 => stepup_1 += 1,
                     2 => stepup_2 += 1,
                     3 => stepup_3 += 1,
-                    _ => {
-                        panic!("This really shouldn't happen");
+                    difference => {
+                        bail!(
+                            "Can't go from {} jolts to {} jolts, that's a difference of {}",
+                            previous,
+                            next,
+                            difference
+                        );
                     },
                 }
 
                 previous = next;
             },
-            None => return (stepup_1, stepup_2, stepup_3 + 1),
+            None => return Ok((stepup_1, stepup_2, stepup_3 + 1)),
         }
     }
 }
@@ -72,26 +78,48 @@ fn calculate_step_up_differences(mut input: Vec<u32>) -> (u32, u32, u32) {
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let input: Vec<u32> = include_str!("day_10/input.txt")
-            .lines()
-            .map(|s| s.parse::<u32>().unwrap())
-            .collect();
+    type Input = Vec<u32>;
 
-        let (s1, _, s3) = calculate_step_up_differences(input);
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 10,
+            title: "Adapter Array",
+        }
+    }
 
-        PartSolution::U32(s1 * s3)
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_10/input.txt")
     }
 
-    fn part_2(&self) -> PartSolution {
-        let input: Vec<u32> = include_str!("day_10/input.txt")
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        input
             .lines()
-            .map(|s| s.parse::<u32>().unwrap())
-            .collect();
+            .map(|s| {
+                s.parse::<u32>()
+                    .wrap_err_with(|| format!("`{}` is not a number", s))
+            })
+            .collect()
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let (s1, _, s3) = calculate_step_up_differences(input.clone())?;
+
+        Ok(PartSolution::U32(s1 * s3))
+    }
 
-        let total = calculate_possibilities(input);
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let total = calculate_possibilities(input.clone())?;
 
-        PartSolution::U64(total)
+        Ok(PartSolution::U64(total))
     }
 }
 
@@ -102,11 +130,21 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_10::{Solution, calculate_step_up_differences};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(1820), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U32(1820),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
@@ -116,7 +154,7 @@ mod tests {
                 .map(|s| s.parse::<u32>().unwrap())
                 .collect();
 
-            let (stepup_1, _stepup_2, stepup_3) = calculate_step_up_differences(input);
+            let (stepup_1, _stepup_2, stepup_3) = calculate_step_up_differences(input).unwrap();
 
             assert_eq!(7, stepup_1);
             assert_eq!(5, stepup_3);
@@ -133,7 +171,7 @@ mod tests {
             .map(|s| s.parse::<u32>().unwrap())
             .collect();
 
-            let (stepup_1, _stepup_2, stepup_3) = calculate_step_up_differences(input);
+            let (stepup_1, _stepup_2, stepup_3) = calculate_step_up_differences(input).unwrap();
 
             assert_eq!(22, stepup_1);
             assert_eq!(10, stepup_3);
@@ -144,11 +182,21 @@ mod tests {
         use pretty_assertions::assert_eq;
 
         use crate::day_10::{Solution, calculate_possibilities};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U64(3_454_189_699_072), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::U64(3_454_189_699_072),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
@@ -158,7 +206,7 @@ mod tests {
                 .map(|s| s.parse::<u32>().unwrap())
                 .collect();
 
-            let total = calculate_possibilities(input);
+            let total = calculate_possibilities(input).unwrap();
 
             assert_eq!(8, total);
         }
@@ -174,7 +222,7 @@ mod tests {
             .map(|s| s.parse::<u32>().unwrap())
             .collect();
 
-            let total = calculate_possibilities(input);
+            let total = calculate_possibilities(input).unwrap();
 
             assert_eq!(19208, total);
         }
@@ -1,53 +1,47 @@
 use std::fmt::Display;
 
-use self::part_1::flip_board_part_1;
-use self::part_2::flip_board_part_2;
-use crate::shared::{Day, PartSolution};
+use color_eyre::eyre::bail;
+use shared::automaton::{Automaton as _, GridAutomaton};
+use shared::grid::Grid;
+
+use self::part_1::seating_part_1;
+use self::part_2::seating_part_2;
+use self::torus::rounds_until_repeating;
+use crate::shared::{
+    BonusPart, Cancellation, Cancelled, Context, Day, Frame, Metadata, PartSolution, Visualize,
+};
 
 mod part_1;
 mod part_2;
+mod torus;
 
-#[derive(PartialEq, Eq)]
-struct Board {
-    number_of_rows: usize,
-    number_of_cols: usize,
-    v_now: Vec<Vec<Thing>>,
-    v_next: Vec<Vec<Thing>>,
-}
-
-impl Board {
-    fn new(cells: Vec<Vec<Thing>>) -> Self {
-        Self {
-            number_of_rows: cells.len(),
-            number_of_cols: cells.first().map(Vec::len).unwrap_or_default(),
-            v_now: cells.clone(),
-            v_next: cells,
-        }
-    }
-}
-
-fn parse_lines(lines: &[&str]) -> Board {
-    let mut cells = Vec::new();
-    for line in lines {
-        let mut row = Vec::new();
-
-        for char in line.chars() {
-            row.push(char.into());
-        }
-
-        cells.push(row);
-    }
+/// The seats changing round after round, the parts differ in which seats people look at and how many occupied ones
+/// they tolerate.
+type Seating = GridAutomaton<Thing, fn(&Thing, &[&Thing]) -> Thing>;
 
-    Board::new(cells)
+fn parse_seats(input: &str) -> Result<Grid<Thing>, color_eyre::Report> {
+    Ok(Grid::parse(input, Thing::parse)?)
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Thing {
+/// A spot in the waiting area.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Thing {
     Floor,
     EmptySeat,
     OccupiedSeat,
 }
 
+impl Thing {
+    fn parse(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Thing::Floor),
+            'L' => Some(Thing::EmptySeat),
+            '#' => Some(Thing::OccupiedSeat),
+            _ => None,
+        }
+    }
+}
+
 impl Display for Thing {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
@@ -58,112 +52,174 @@ impl Display for Thing {
     }
 }
 
-impl From<Thing> for char {
-    fn from(t: Thing) -> Self {
-        match t {
-            Thing::Floor => '.',
-            Thing::EmptySeat => 'L',
-            Thing::OccupiedSeat => '#',
-        }
+/// What `thing` becomes next round, people leave a seat when `tolerance` or more of the seats they look at are
+/// occupied.
+fn next_state(thing: Thing, neighbours: &[&Thing], tolerance: usize) -> Thing {
+    let occupied = neighbours
+        .iter()
+        .filter(|&&&neighbour| neighbour == Thing::OccupiedSeat)
+        .count();
+
+    match thing {
+        // empty seat with no occupied seats becomes occupied
+        Thing::EmptySeat if occupied == 0 => Thing::OccupiedSeat,
+        // occupied seat with too many occupied seats becomes empty
+        Thing::OccupiedSeat if occupied >= tolerance => Thing::EmptySeat,
+        Thing::Floor | Thing::EmptySeat | Thing::OccupiedSeat => thing,
     }
 }
-impl From<char> for Thing {
-    fn from(c: char) -> Self {
-        match c {
-            '.' => Thing::Floor,
-            'L' => Thing::EmptySeat,
-            '#' => Thing::OccupiedSeat,
-            _ => unreachable!(),
-        }
-    }
+
+fn count_occupied(seats: &Grid<Thing>) -> usize {
+    seats
+        .cells()
+        .filter(|&&thing| thing == Thing::OccupiedSeat)
+        .count()
 }
 
-fn count_occupied(board: &Board) -> usize {
-    board
-        .v_now
-        .iter()
-        .map(|row| row.iter().filter(|&&v| v == Thing::OccupiedSeat).count())
-        .sum()
+fn rows(seats: &Grid<Thing>) -> Vec<String> {
+    seats.to_string().lines().map(str::to_owned).collect()
 }
 
-fn flip_board_until_stable_part_1(mut board: Board) -> usize {
-    while flip_board_part_1(&mut board) {}
+/// Flips the seats until nothing changes, showing `on_flip` the seats before the first flip and after every flip that
+/// changed them.
+fn flip_until_stable(
+    mut seating: Seating,
+    cancellation: &Cancellation,
+    on_flip: &mut dyn FnMut(&Grid<Thing>),
+) -> Result<usize, Cancelled> {
+    on_flip(seating.cells());
 
-    count_occupied(&board)
-}
+    seating.run_until_stable(|seats| {
+        on_flip(seats);
 
-fn flip_board_until_stable_part_2(mut board: Board) -> usize {
-    while flip_board_part_2(&mut board) {}
+        cancellation.check()
+    })?;
 
-    count_occupied(&board)
+    Ok(count_occupied(seating.cells()))
 }
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_11/input.txt").lines().collect();
-
-        let board = parse_lines(&lines);
+    type Input = Grid<Thing>;
 
-        let answer = flip_board_until_stable_part_1(board);
-
-        PartSolution::USize(answer)
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 11,
+            title: "Seating System",
+        }
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_11/input.txt").lines().collect();
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_11/input.txt")
+    }
 
-        let board = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        parse_seats(input)
+    }
 
-        let answer = flip_board_until_stable_part_2(board);
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let answer = flip_until_stable(
+            seating_part_1(input.clone()),
+            context.cancellation(),
+            &mut |_| {},
+        )?;
+
+        Ok(PartSolution::USize(answer))
+    }
 
-        PartSolution::USize(answer)
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let answer = flip_until_stable(
+            seating_part_2(input.clone()),
+            context.cancellation(),
+            &mut |_| {},
+        )?;
+
+        Ok(PartSolution::USize(answer))
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::Board;
+    fn bonus_parts(&self) -> &'static [BonusPart<Self>] {
+        &[BonusPart {
+            name: "torus",
+            solve: |_, input, context| {
+                let rounds = rounds_until_repeating(input.clone(), context.cancellation())?;
 
-    fn get_example() -> Vec<&'static str> {
-        include_str!("day_11/example.txt").lines().collect()
+                Ok(PartSolution::USize(rounds))
+            },
+        }]
     }
 
-    fn pretty_print(board: &Board) -> String {
-        let mut lines = Vec::new();
-        for row in &board.v_now {
-            let mut line = Vec::<char>::new();
-
-            for value in row {
-                line.push((*value).into());
-            }
+    fn visualizer(&self) -> Option<&dyn Visualize<Input = Self::Input>> {
+        Some(self)
+    }
+}
 
-            lines.push(line.iter().collect::<String>());
-        }
+impl Visualize for Solution {
+    fn visualize(
+        &self,
+        part: u32,
+        input: &Self::Input,
+        context: &Context,
+        frames: &mut dyn FnMut(Frame),
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let seating = match part {
+            1 => seating_part_1(input.clone()),
+            2 => seating_part_2(input.clone()),
+            _ => bail!("Part {} does not exist", part),
+        };
+
+        let answer = flip_until_stable(seating, context.cancellation(), &mut |seats| {
+            frames(Frame::Grid(rows(seats)));
+        })?;
+
+        Ok(PartSolution::USize(answer))
+    }
+}
 
-        lines.join("\n")
+#[cfg(test)]
+mod test {
+    fn get_example() -> &'static str {
+        include_str!("day_11/example.txt")
     }
 
     mod part_1 {
         use pretty_assertions::assert_eq;
+        use shared::automaton::Automaton as _;
 
-        use crate::day_11::part_1::flip_board_part_1;
-        use crate::day_11::test::{get_example, pretty_print};
-        use crate::day_11::{Solution, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_11::part_1::seating_part_1;
+        use crate::day_11::test::get_example;
+        use crate::day_11::{Solution, parse_seats, rows};
+        use crate::shared::{Context, Day as _, Frame, PartSolution, Visualize as _};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(2406), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(2406),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
+            let mut seating = seating_part_1(parse_seats(get_example()).unwrap());
 
-            let mut board = parse_lines(&lines);
-
-            flip_board_part_1(&mut board);
+            seating.step();
 
             let expected = [
                 "#.##.##.##",
@@ -179,9 +235,9 @@ mod test {
             ]
             .join("\n");
 
-            assert_eq!(expected, pretty_print(&board));
+            assert_eq!(expected, rows(seating.cells()).join("\n"));
 
-            flip_board_part_1(&mut board);
+            seating.step();
 
             let expected = [
                 "#.LL.L#.##",
@@ -197,30 +253,58 @@ mod test {
             ]
             .join("\n");
 
-            assert_eq!(expected, pretty_print(&board));
+            assert_eq!(expected, rows(seating.cells()).join("\n"));
+        }
+
+        #[test]
+        fn visualize() {
+            let solution = Solution {};
+
+            let seats = parse_seats(get_example()).unwrap();
+
+            let mut frames = Vec::new();
+
+            let answer = solution
+                .visualize(1, &seats, &Context::default(), &mut |frame| {
+                    frames.push(frame);
+                })
+                .unwrap();
+
+            assert_eq!(answer, PartSolution::USize(37));
+            assert_eq!(frames.len(), 6);
+            assert_eq!(frames.first(), Some(&Frame::Grid(rows(&seats))));
         }
     }
 
     mod part_2 {
         use pretty_assertions::assert_eq;
+        use shared::automaton::Automaton as _;
 
-        use crate::day_11::part_2::flip_board_part_2;
-        use crate::day_11::test::{get_example, pretty_print};
-        use crate::day_11::{Solution, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_11::part_2::seating_part_2;
+        use crate::day_11::test::get_example;
+        use crate::day_11::{Solution, parse_seats, rows};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(2149), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(2149),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
+            let mut seating = seating_part_2(parse_seats(get_example()).unwrap());
 
-            let mut board = parse_lines(&lines);
-
-            flip_board_part_2(&mut board);
+            seating.step();
 
             let expected = [
                 "#.##.##.##",
@@ -236,9 +320,9 @@ mod test {
             ]
             .join("\n");
 
-            assert_eq!(expected, pretty_print(&board));
+            assert_eq!(expected, rows(seating.cells()).join("\n"));
 
-            flip_board_part_2(&mut board);
+            seating.step();
 
             // If a seat is empty (L) and there are no occupied seats adjacent to it, the seat becomes occupied.
             // If a seat is occupied (#) and five or more seats adjacent to it are also occupied, the seat becomes empty.
@@ -258,7 +342,47 @@ mod test {
             ]
             .join("\n");
 
-            assert_eq!(expected, pretty_print(&board));
+            assert_eq!(expected, rows(seating.cells()).join("\n"));
+        }
+    }
+
+    mod torus {
+        use pretty_assertions::assert_eq;
+
+        use crate::day_11::test::get_example;
+        use crate::day_11::torus::rounds_until_repeating;
+        use crate::day_11::{Solution, parse_seats};
+        use crate::shared::{Context, Day as _, PartSolution};
+
+        #[test]
+        fn outcome() {
+            let solution = Solution {};
+
+            let torus = solution
+                .bonus_parts()
+                .iter()
+                .find(|bonus| bonus.name == "torus")
+                .unwrap();
+
+            assert_eq!(
+                PartSolution::USize(140),
+                (torus.solve)(
+                    &solution,
+                    &solution.parse(solution.embedded_input()).unwrap(),
+                    &Context::default(),
+                )
+                .unwrap()
+            );
+        }
+
+        #[test]
+        fn example() {
+            let seats = parse_seats(get_example()).unwrap();
+
+            assert_eq!(
+                12,
+                rounds_until_repeating(seats, Context::default().cancellation()).unwrap()
+            );
         }
     }
 }
@@ -0,0 +1,31 @@
+use shared::automaton::{Automaton as _, GridAutomaton, Neighbourhood};
+use shared::grid::{Coordinate, Direction, Grid};
+
+use super::{Seating, Thing, next_state};
+use crate::shared::{Cancellation, Cancelled};
+
+/// With the seats wrapped around, the seating doesn't have to settle, it can keep flipping between the same seatings
+/// forever. Returns after how many rounds it starts repeating, a stable seating repeats after one round.
+pub(super) fn rounds_until_repeating(
+    seats: Grid<Thing>,
+    cancellation: &Cancellation,
+) -> Result<usize, Cancelled> {
+    let mut seating: Seating = GridAutomaton::new(
+        seats,
+        Neighbourhood::Custom(around_the_torus),
+        |&thing, neighbours| next_state(thing, neighbours, 4),
+    );
+
+    let repetition = seating.run_until_repeating(|_| cancellation.check())?;
+
+    Ok(repetition.start)
+}
+
+/// The seats around `coordinate` with the rules of part 1, but the seats along each edge are adjacent to the seats
+/// along the opposite edge, as if the waiting area were wrapped around a torus.
+fn around_the_torus(seats: &Grid<Thing>, coordinate: Coordinate) -> Vec<Coordinate> {
+    Direction::ALL
+        .into_iter()
+        .map(|direction| seats.step_wrapping(coordinate, direction.offset()))
+        .collect()
+}
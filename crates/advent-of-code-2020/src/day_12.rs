@@ -1,12 +1,26 @@
-use crate::shared::{Day, PartSolution};
+use color_eyre::eyre::{WrapErr as _, bail};
 
-fn parse_lines(lines: &[&str]) -> Vec<Operation> {
+use crate::shared::{Context, Day, Frame, Metadata, PartSolution, Visualize};
+
+fn parse_lines(lines: &[&str]) -> Result<Vec<Operation>, color_eyre::Report> {
     let mut result = Vec::new();
 
-    for line in lines {
-        let (op, value) = line.split_at(1);
+    for (line_number, line) in (1..).zip(lines) {
+        let Some((op, value)) = line.split_at_checked(1) else {
+            bail!("Line {}: expected `<action><value>`, got `{}`", line_number, line);
+        };
+
+        let v: i32 = value
+            .parse()
+            .wrap_err_with(|| format!("Line {}: `{}` is not a value", line_number, value))?;
 
-        let v: i32 = value.parse().unwrap();
+        if (op == "L" || op == "R") && ![90, 180, 270].contains(&v) {
+            bail!(
+                "Line {}: can only turn 90, 180 or 270 degrees, not {}",
+                line_number,
+                v
+            );
+        }
 
         let operation = match op.as_bytes()[0] {
             b'N' => Operation::MoveNorth(v),
@@ -16,16 +30,16 @@ fn parse_lines(lines: &[&str]) -> Vec<Operation> {
             b'L' => Operation::RotateLeft(v),
             b'R' => Operation::RotateRight(v),
             b'F' => Operation::MoveForward(v),
-            _ => panic!("AT THE DISCOTHEQUE"),
+            _ => bail!("Line {}: unknown action `{}`", line_number, op),
         };
 
         result.push(operation);
     }
 
-    result
+    Ok(result)
 }
 
-enum Operation {
+pub enum Operation {
     MoveNorth(i32),
     MoveSouth(i32),
     MoveEast(i32),
@@ -71,9 +85,7 @@ impl Direction {
             180 => *self = Direction::South,
             270 => *self = Direction::West,
             _ => {
-                panic!(
-                    "Where we're going, we don't need roads... I mean, what? Where ARE we going?"
-                )
+                unreachable!("Turns are checked to be 90, 180 or 270 degrees when parsing")
             },
         }
     }
@@ -179,7 +191,7 @@ impl ShipAndWaypoint {
                         );
                     },
                     _ => {
-                        panic!("You spin me right round baby right round")
+                        unreachable!("Turns are checked to be 90, 180 or 270 degrees when parsing")
                     },
                 }
             },
@@ -191,21 +203,38 @@ impl ShipAndWaypoint {
     }
 }
 
-fn pilot(operations: Vec<Operation>) -> i32 {
+fn pilot(operations: &[Operation]) -> i32 {
+    pilot_with(operations, &mut |_| {})
+}
+
+/// Pilots the ship, showing `on_move` where it is after every operation.
+fn pilot_with(operations: &[Operation], on_move: &mut dyn FnMut((i32, i32))) -> i32 {
     let mut ship = Ship::new();
 
     for operation in operations {
-        ship.process_operation(&operation);
+        ship.process_operation(operation);
+
+        on_move((ship.location_x, ship.location_y));
     }
 
     ship.location_x.abs() + ship.location_y.abs()
 }
 
-fn pilot_part_2(operations: Vec<Operation>) -> i32 {
+fn pilot_part_2(operations: &[Operation]) -> i32 {
+    pilot_part_2_with(operations, &mut |_| {})
+}
+
+/// Pilots the ship with the waypoint, showing `on_move` where the ship is after every operation.
+fn pilot_part_2_with(operations: &[Operation], on_move: &mut dyn FnMut((i32, i32))) -> i32 {
     let mut ship_and_waypoint = ShipAndWaypoint::new();
 
     for operation in operations {
-        ship_and_waypoint.process_operation_part_2(&operation);
+        ship_and_waypoint.process_operation_part_2(operation);
+
+        on_move((
+            ship_and_waypoint.ship_location_x,
+            ship_and_waypoint.ship_location_y,
+        ));
     }
 
     ship_and_waypoint.ship_location_x.abs() + ship_and_waypoint.ship_location_y.abs()
@@ -214,24 +243,84 @@ fn pilot_part_2(operations: Vec<Operation>) -> i32 {
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_12/input.txt").lines().collect();
+    type Input = Vec<Operation>;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 12,
+            title: "Rain Risk",
+        }
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_12/input.txt")
+    }
 
-        let operations = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let score = pilot(operations);
+        parse_lines(&lines)
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let score = pilot(input);
+
+        Ok(score.into())
+    }
 
-        score.into()
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let score = pilot_part_2(input);
+
+        Ok(score.into())
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_12/input.txt").lines().collect();
+    fn visualizer(&self) -> Option<&dyn Visualize<Input = Self::Input>> {
+        Some(self)
+    }
+}
 
-        let parsed = parse_lines(&lines);
+impl Visualize for Solution {
+    /// Draws the path of the ship so far, whenever it moved.
+    fn visualize(
+        &self,
+        part: u32,
+        input: &Self::Input,
+        _context: &Context,
+        frames: &mut dyn FnMut(Frame),
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let pilot = match part {
+            1 => pilot_with,
+            2 => pilot_part_2_with,
+            _ => bail!("Part {} does not exist", part),
+        };
 
-        let score = pilot_part_2(parsed);
+        let mut path = vec![(0, 0)];
 
-        score.into()
+        let score = pilot(input, &mut |(x, y)| {
+            let location = (i64::from(x), i64::from(y));
+
+            if path.last() != Some(&location) {
+                path.push(location);
+
+                frames(Frame::Points(path.clone()));
+            }
+        });
+
+        frames(Frame::Text(format!(
+            "The ship ended {} from where it started",
+            score
+        )));
+
+        Ok(score.into())
     }
 }
 
@@ -246,23 +335,60 @@ mod test {
 
         use crate::day_12::test::get_example;
         use crate::day_12::{Solution, parse_lines, pilot};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, Frame, PartSolution, Visualize as _};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::I32(2847), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::I32(2847),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn example() {
             let lines: Vec<&str> = get_example();
 
-            let operations = parse_lines(&lines);
+            let operations = parse_lines(&lines).unwrap();
 
-            let score = pilot(operations);
+            let score = pilot(&operations);
 
             assert_eq!(score, 25);
         }
+
+        #[test]
+        fn visualize() {
+            let solution = Solution {};
+
+            let operations = parse_lines(&get_example()).unwrap();
+
+            let mut frames = Vec::new();
+
+            let answer = solution
+                .visualize(1, &operations, &Context::default(), &mut |frame| {
+                    frames.push(frame);
+                })
+                .unwrap();
+
+            assert_eq!(answer, PartSolution::I32(25));
+            assert_eq!(
+                frames,
+                vec![
+                    Frame::Points(vec![(0, 0), (10, 0)]),
+                    Frame::Points(vec![(0, 0), (10, 0), (10, -3)]),
+                    Frame::Points(vec![(0, 0), (10, 0), (10, -3), (17, -3)]),
+                    Frame::Points(vec![(0, 0), (10, 0), (10, -3), (17, -3), (17, 8)]),
+                    Frame::Text("The ship ended 25 from where it started".to_owned()),
+                ]
+            );
+        }
     }
 
     mod part_2 {
@@ -270,20 +396,30 @@ mod test {
 
         use crate::day_12::test::get_example;
         use crate::day_12::{Solution, parse_lines, pilot_part_2};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::I32(29839), (Solution {}).part_2());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::I32(29839),
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn example() {
             let lines: Vec<&str> = get_example();
 
-            let operations = parse_lines(&lines);
+            let operations = parse_lines(&lines).unwrap();
 
-            let score = pilot_part_2(operations);
+            let score = pilot_part_2(&operations);
 
             assert_eq!(score, 286);
         }
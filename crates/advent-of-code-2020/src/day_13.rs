@@ -1,31 +1,53 @@
-use crate::shared::{Day, PartSolution};
+use color_eyre::eyre::{OptionExt as _, WrapErr as _, bail};
+use shared::math::Congruence;
 
-fn parse_lines_part_1(lines: &[&str]) -> (usize, Vec<usize>) {
-    let time = lines[0].parse::<usize>().unwrap();
+use crate::shared::{Context, Day, Metadata, Note, PartSolution};
 
-    let buses = lines[1]
+fn parse_bus(bus: &str) -> Result<usize, color_eyre::Report> {
+    match bus.parse::<usize>() {
+        Ok(0) => bail!("Bus 0 never departs"),
+        Ok(bus) => Ok(bus),
+        Err(error) => Err(error).wrap_err_with(|| format!("`{}` is not a bus", bus)),
+    }
+}
+
+fn parse_lines_part_1(lines: &[&str]) -> Result<(usize, Vec<usize>), color_eyre::Report> {
+    let &[time, buses, ..] = lines else {
+        bail!(
+            "Expected the earliest departure and the buses, got {} line(s)",
+            lines.len()
+        );
+    };
+
+    let time = time
+        .parse::<usize>()
+        .wrap_err_with(|| format!("`{}` is not a departure time", time))?;
+
+    let buses = buses
         .split(',')
         .filter(|&v| v != "x")
-        .map(|v| v.parse::<usize>().unwrap())
-        .collect::<Vec<_>>();
+        .map(parse_bus)
+        .collect::<Result<Vec<_>, _>>()?;
 
-    (time, buses)
+    Ok((time, buses))
 }
 
-fn parse_lines_part_2(lines: &[&str]) -> Vec<(usize, usize)> {
-    let buses = lines[1]
+fn parse_lines_part_2(lines: &[&str]) -> Result<Vec<(usize, usize)>, color_eyre::Report> {
+    let buses = lines
+        .get(1)
+        .ok_or_eyre("Missing the line with the buses")?
         .split(',')
         .enumerate()
         .filter(|&(_, v)| v != "x")
-        .map(|(i, v)| (i, v.parse::<usize>().unwrap()))
-        .collect::<Vec<_>>();
+        .map(|(i, v)| Ok((i, parse_bus(v)?)))
+        .collect::<Result<Vec<_>, color_eyre::Report>>()?;
 
-    buses
+    Ok(buses)
 }
-fn find_closest(time: usize, buses: Vec<usize>) -> usize {
+fn find_closest(time: usize, buses: &[usize]) -> usize {
     let mut lowest_wait_time = usize::MAX;
     let mut lowest_wait_time_bus = 0;
-    for bus in buses {
+    for &bus in buses {
         let wait_time = bus - (time % bus);
 
         if wait_time < lowest_wait_time {
@@ -37,45 +59,97 @@ fn find_closest(time: usize, buses: Vec<usize>) -> usize {
     lowest_wait_time * lowest_wait_time_bus
 }
 
-fn find_one_minute_apart(mut buses: Vec<(usize, usize)>) -> usize {
-    buses.sort_by_key(|b| b.0);
-
-    // example starts with 7
-    let mut step_size = buses.first().unwrap().1;
-    let mut time = 0;
-
-    buses.iter().skip(1).for_each(|&(offset, bus)| {
-        while (time + offset) % bus != 0 {
-            time += step_size;
+fn find_one_minute_apart(
+    buses: &[(usize, usize)],
+    context: &Context,
+) -> Result<usize, color_eyre::Report> {
+    let mut schedule = Congruence::ANY;
+
+    for (index, &(offset, bus)) in buses.iter().enumerate() {
+        let bus = u64::try_from(bus)?;
+        let offset = u64::try_from(offset)?;
+
+        // the bus departs `offset` minutes after the time, so the time is `-offset` modulo the bus
+        let departure =
+            Congruence::new(bus - offset % bus, bus).ok_or_eyre("Bus 0 never departs")?;
+
+        schedule = schedule.combine(departure).wrap_err_with(|| {
+            format!(
+                "Bus {} can't depart {} minute(s) after the first one",
+                bus, offset
+            )
+        })?;
+
+        if index > 0 {
+            context.explain().note(|| {
+                Note::new(format!("Aligned bus {}, {} minute(s) after the first one", bus, offset))
+                    .with("time", schedule.residue())
+                    .with("repeats every", schedule.modulus())
+            });
         }
+    }
 
-        step_size *= bus;
-    });
+    Ok(usize::try_from(schedule.residue())?)
+}
 
-    time
+/// The notes, read the way each part needs them.
+pub struct Notes {
+    earliest: usize,
+    buses: Vec<usize>,
+    buses_with_offset: Vec<(usize, usize)>,
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_13/input.txt").lines().collect();
+    type Input = Notes;
 
-        let (time, buses) = parse_lines_part_1(&lines);
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: 13,
+            title: "Shuttle Search",
+        }
+    }
 
-        let score = find_closest(time, buses);
+    fn embedded_input(&self) -> &'static str {
+        include_str!("day_13/input.txt")
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        let lines: Vec<&str> = input.lines().collect();
+
+        let (earliest, buses) = parse_lines_part_1(&lines)?;
+
+        if buses.is_empty() {
+            bail!("There are no buses");
+        }
 
-        score.into()
+        Ok(Notes {
+            earliest,
+            buses,
+            buses_with_offset: parse_lines_part_2(&lines)?,
+        })
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_13/input.txt").lines().collect();
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        _context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let score = find_closest(input.earliest, &input.buses);
 
-        let buses = parse_lines_part_2(&lines);
+        Ok(score.into())
+    }
 
-        let score = find_one_minute_apart(buses);
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let score = find_one_minute_apart(&input.buses_with_offset, context)?;
 
-        score.into()
+        Ok(score.into())
     }
 }
 
@@ -91,37 +165,56 @@ mod test {
 
         use crate::day_13::test::get_example;
         use crate::day_13::{Solution, find_closest, parse_lines_part_1};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Context, Day as _, PartSolution};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::USize(119), (Solution {}).part_1());
+            let solution = Solution {};
+
+            assert_eq!(
+                PartSolution::USize(119),
+                solution
+                    .part_1(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
+            );
         }
 
         #[test]
         fn example() {
             let lines: Vec<&str> = get_example();
 
-            let (time, buses) = parse_lines_part_1(&lines);
+            let (time, buses) = parse_lines_part_1(&lines).unwrap();
 
-            let score = find_closest(time, buses);
+            let score = find_closest(time, &buses);
 
             assert_eq!(score, 295);
         }
     }
 
     mod part_2 {
+        use std::sync::Arc;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_13::test::get_example;
         use crate::day_13::{Solution, find_one_minute_apart, parse_lines_part_2};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{Cancellation, Context, Day as _, Explain, PartSolution};
 
         #[test]
         fn outcome() {
+            let solution = Solution {};
+
             assert_eq!(
                 PartSolution::USize(1_106_724_616_194_525),
-                (Solution {}).part_2()
+                solution
+                    .part_2(
+                        &solution.parse(solution.embedded_input()).unwrap(),
+                        &Context::default(),
+                    )
+                    .unwrap()
             );
         }
 
@@ -129,11 +222,57 @@ mod test {
         fn example() {
             let lines: Vec<&str> = get_example();
 
-            let buses = parse_lines_part_2(&lines);
+            let buses = parse_lines_part_2(&lines).unwrap();
 
-            let score = find_one_minute_apart(buses);
+            let score = find_one_minute_apart(&buses, &Context::default()).unwrap();
 
             assert_eq!(score, 1_068_781);
         }
+
+        #[test]
+        fn inconsistent_schedule() {
+            let solution = Solution {};
+
+            let error = solution
+                .part_2(&solution.parse("0\n2,4").unwrap(), &Context::default())
+                .unwrap_err();
+
+            assert_eq!(
+                format!("{:#}", error),
+                "Bus 4 can't depart 1 minute(s) after the first one: The congruences have no common solution"
+            );
+        }
+
+        #[test]
+        fn shared_factors() {
+            let buses = parse_lines_part_2(&["0", "4,x,6"]).unwrap();
+
+            assert_eq!(find_one_minute_apart(&buses, &Context::default()).unwrap(), 4);
+        }
+
+        #[test]
+        fn explain() {
+            let buses = parse_lines_part_2(&get_example()).unwrap();
+
+            let context = Context::new(Cancellation::default(), Arc::default(), Explain::enabled());
+
+            find_one_minute_apart(&buses, &context).unwrap();
+
+            let notes: Vec<String> = context
+                .into_notes()
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+
+            assert_eq!(
+                notes,
+                vec![
+                    "Aligned bus 13, 1 minute(s) after the first one: time = 77, repeats every = 91",
+                    "Aligned bus 59, 4 minute(s) after the first one: time = 350, repeats every = 5369",
+                    "Aligned bus 31, 6 minute(s) after the first one: time = 70147, repeats every = 166439",
+                    "Aligned bus 19, 7 minute(s) after the first one: time = 1068781, repeats every = 3162341",
+                ]
+            );
+        }
     }
 }
@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::WrapErr as _;
+
+/// Where the runner gets the puzzle input for a day from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// The input that was compiled into the binary.
+    Embedded,
+    /// A single input, read from standard input.
+    Stdin,
+    /// A single input, read from a file.
+    File(PathBuf),
+    /// One input per day, read from `<DIR>/day_XX.txt`.
+    Directory(PathBuf),
+}
+
+impl InputSource {
+    pub fn new(input: Option<&Path>, inputs_dir: Option<&Path>) -> Self {
+        match (input, inputs_dir) {
+            (Some(path), _) if path == Path::new("-") => InputSource::Stdin,
+            (Some(path), _) => InputSource::File(path.to_path_buf()),
+            (None, Some(dir)) => InputSource::Directory(dir.to_path_buf()),
+            (None, None) => InputSource::Embedded,
+        }
+    }
+
+    /// Whether this source provides the same input for every day, which only makes sense for a single day.
+    pub fn is_single_input(&self) -> bool {
+        matches!(*self, InputSource::Stdin | InputSource::File(_))
+    }
+
+    /// The name answers for this input are recorded under.
+    pub fn name(&self) -> String {
+        match *self {
+            InputSource::Embedded => "input".to_owned(),
+            InputSource::Stdin => "stdin".to_owned(),
+            InputSource::File(ref path) => file_name(path, Path::file_stem),
+            InputSource::Directory(ref dir) => file_name(dir, Path::file_name),
+        }
+    }
+
+    pub fn load(
+        &self,
+        day: u32,
+        embedded: &'static str,
+    ) -> Result<Cow<'static, str>, color_eyre::Report> {
+        match *self {
+            InputSource::Embedded => Ok(Cow::Borrowed(embedded)),
+            InputSource::Stdin => {
+                let mut input = String::new();
+
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .wrap_err("Failed to read input from stdin")?;
+
+                Ok(Cow::Owned(input))
+            },
+            InputSource::File(ref path) => read_file(path),
+            InputSource::Directory(ref dir) => read_file(&dir.join(format!("day_{:02}.txt", day))),
+        }
+    }
+}
+
+fn file_name<'p, F>(path: &'p Path, name: F) -> String
+where
+    F: FnOnce(&'p Path) -> Option<&'p OsStr>,
+{
+    name(path).map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
+
+fn read_file(path: &Path) -> Result<Cow<'static, str>, color_eyre::Report> {
+    std::fs::read_to_string(path)
+        .map(Cow::Owned)
+        .wrap_err_with(|| format!("Failed to read input from {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::input::InputSource;
+
+    #[test]
+    fn source_from_arguments() {
+        assert_eq!(InputSource::new(None, None), InputSource::Embedded);
+        assert_eq!(
+            InputSource::new(Some(Path::new("-")), None),
+            InputSource::Stdin
+        );
+        assert_eq!(
+            InputSource::new(Some(Path::new("day_07.txt")), Some(Path::new("inputs"))),
+            InputSource::File("day_07.txt".into())
+        );
+        assert_eq!(
+            InputSource::new(None, Some(Path::new("inputs"))),
+            InputSource::Directory("inputs".into())
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(InputSource::Embedded.name(), "input");
+        assert_eq!(InputSource::Stdin.name(), "stdin");
+        assert_eq!(
+            InputSource::File("day_11/example.txt".into()).name(),
+            "example"
+        );
+        assert_eq!(
+            InputSource::Directory("inputs/alice/".into()).name(),
+            "alice"
+        );
+    }
+
+    #[test]
+    fn embedded() {
+        assert_eq!(
+            InputSource::Embedded.load(1, "1721\n979").unwrap(),
+            "1721\n979"
+        );
+    }
+
+    #[test]
+    fn directory() {
+        let dir = std::env::temp_dir().join(format!("aoc-2020-inputs-{}", std::process::id()));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day_07.txt"), "from disk").unwrap();
+
+        let source = InputSource::Directory(dir.clone());
+
+        assert_eq!(source.load(7, "embedded").unwrap(), "from disk");
+        assert_eq!(
+            source.load(8, "embedded").unwrap_err().to_string(),
+            format!(
+                "Failed to read input from {}",
+                dir.join("day_08.txt").display()
+            )
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
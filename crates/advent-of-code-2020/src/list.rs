@@ -0,0 +1,95 @@
+use crate::registry::Registry;
+use crate::table::render_table;
+
+/// Renders every registered day with its title and parts, and a row for each of its parameters.
+pub fn list(solutions: &Registry) -> String {
+    let mut rows = Vec::new();
+
+    for day in solutions.days() {
+        let solution = &solutions[day];
+        let metadata = solution.metadata();
+
+        let parts: Vec<String> = solution.parts().iter().map(ToString::to_string).collect();
+
+        let row = |param: &str, default: &str, description: &str| {
+            [
+                metadata.year.to_string(),
+                metadata.day.to_string(),
+                metadata.title.to_owned(),
+                parts.join(", "),
+                param.to_owned(),
+                default.to_owned(),
+                description.to_owned(),
+            ]
+        };
+
+        if solution.params().is_empty() {
+            rows.push(row("", "", ""));
+        }
+
+        for param in solution.params() {
+            rows.push(row(param.name, param.default, param.description));
+        }
+    }
+
+    render_table(
+        &[
+            "Year",
+            "Day",
+            "Title",
+            "Parts",
+            "Parameter",
+            "Default",
+            "Description",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::list::list;
+    use crate::registrations;
+    use crate::registry::Registry;
+
+    #[test]
+    fn titles_and_params() {
+        let listed = list(&Registry::new(registrations()).unwrap());
+
+        let lines: Vec<Vec<&str>> = listed
+            .lines()
+            .map(|line| {
+                line.split("  ")
+                    .map(str::trim)
+                    .filter(|cell| !cell.is_empty())
+                    .collect()
+            })
+            .collect();
+
+        assert_eq!(
+            lines[0],
+            vec![
+                "Year",
+                "Day",
+                "Title",
+                "Parts",
+                "Parameter",
+                "Default",
+                "Description"
+            ]
+        );
+        assert!(lines.contains(&vec!["2020", "2", "Password Philosophy", "1, 2"]));
+        assert!(lines.contains(&vec!["2020", "11", "Seating System", "1, 2, torus"]));
+        assert!(lines.contains(&vec![
+            "2020",
+            "9",
+            "Encoding Error",
+            "1, 2",
+            "preamble",
+            "25",
+            "How many numbers before each number it needs to be the sum of 2 of."
+        ]));
+    }
+}
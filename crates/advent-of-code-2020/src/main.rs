@@ -1,52 +1,136 @@
-use shared::Day;
+use std::process::ExitCode;
 
+use clap::{CommandFactory as _, FromArgMatches as _};
+
+use crate::cli::{Cli, Command};
+use crate::config::Config;
+use crate::output::Printer;
+use crate::registry::{Registry, register_days};
+use crate::runner::{Outcome, PartResult};
+use crate::visualize::Target;
+
+mod answers;
+mod bench;
+mod cli;
+mod compare;
+mod config;
+mod input;
+mod list;
+mod ocr;
+mod output;
+mod registry;
+mod report;
+mod runner;
+mod serve;
 mod shared;
+mod table;
+mod timing;
 mod utils;
+mod verify;
+mod visualize;
 
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
+register_days![
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11, day_12,
+    day_13,
+];
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-fn print_answer(day: u32, part: u32, result: &str) {
-    println!("Answer to Day {}, part {} is ... {}", day, part, result);
-}
-
-fn main() -> Result<(), color_eyre::Report> {
+fn main() -> Result<ExitCode, color_eyre::Report> {
     color_eyre::install()?;
 
-    let solutions: Vec<Box<dyn Day>> = vec![
-        Box::new(day_01::Solution {}),
-        Box::new(day_02::Solution {}),
-        Box::new(day_03::Solution {}),
-        Box::new(day_04::Solution {}),
-        Box::new(day_05::Solution {}),
-        Box::new(day_06::Solution {}),
-        Box::new(day_07::Solution {}),
-        Box::new(day_08::Solution {}),
-        Box::new(day_09::Solution {}),
-        Box::new(day_10::Solution {}),
-        Box::new(day_11::Solution {}),
-        Box::new(day_12::Solution {}),
-        Box::new(day_13::Solution {}),
-    ];
-
-    for (day, solution) in (1_u32..).zip(solutions) {
-        print_answer(day, 1, &solution.part_1().to_string());
-        print_answer(day, 2, &solution.part_2().to_string());
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches)?;
+
+    let config = Config::load(cli.config.as_deref())?;
+
+    config.apply(&mut cli, &matches)?;
+
+    let solutions = Registry::new(registrations())?;
+
+    if cli.show_config {
+        print!(
+            "{}",
+            toml::to_string_pretty(&config.effective(&cli, &solutions)?)?
+        );
+
+        return Ok(ExitCode::SUCCESS);
     }
 
-    Ok(())
+    let gaps = solutions.gaps();
+
+    if !gaps.is_empty() {
+        eprintln!(
+            "No solution registered for day(s) {}",
+            gaps.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    match cli.command {
+        Some(Command::Verify(ref args)) => verify::verify(&solutions, args),
+        Some(Command::Bench(ref args)) => {
+            bench::bench(&solutions, args)?;
+
+            Ok(ExitCode::SUCCESS)
+        },
+        Some(Command::Compare(ref args)) => compare::compare(&solutions, args),
+        Some(Command::List) => {
+            print!("{}", list::list(&solutions));
+
+            Ok(ExitCode::SUCCESS)
+        },
+        Some(Command::Serve(ref args)) => {
+            serve::serve(solutions, args)?;
+
+            Ok(ExitCode::SUCCESS)
+        },
+        None => {
+            let mut printer = Printer::new(cli.format);
+            let mut failed = false;
+
+            let mut on_result = |result: PartResult| {
+                failed |= !matches!(result.outcome, Outcome::Solved(_));
+
+                printer.print(result, None);
+            };
+
+            match cli.visualize {
+                Some(ref visualize) => visualize::visualize(
+                    &solutions,
+                    cli.selection(),
+                    &Target::new(visualize, cli.frame_delay),
+                    &mut on_result,
+                )?,
+                None => runner::run(&solutions, cli.selection(), &mut on_result)?,
+            }
+
+            printer.finish();
+
+            Ok(if failed {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            })
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::registrations;
+    use crate::registry::Registry;
+
+    #[test]
+    fn every_day_is_registered_once() {
+        let registry = Registry::new(registrations()).unwrap();
+
+        assert_eq!(registry.days(), (1..=13).collect());
+        assert_eq!(registry.gaps(), Vec::<u32>::new());
+    }
 }
@@ -0,0 +1,270 @@
+/// The 4x6 letters, as drawn by most puzzles that answer with text.
+const SMALL_FONT: &[(char, &[&str])] = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The 6x10 letters, as drawn by the puzzles with a bigger screen.
+const LARGE_FONT: &[(char, &[&str])] = &[
+    (
+        'A',
+        &[
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        &[
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        &[
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        &[
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        &[
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        &[
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        &[
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        &[
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        &[
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        &[
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        &[
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        &[
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        &[
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+/// Turns art made of `#` (lit) and anything else (dark) into pixels.
+pub fn parse_art(art: &str) -> Vec<Vec<bool>> {
+    art.lines()
+        .map(|line| line.chars().map(|c| c == '#').collect())
+        .collect()
+}
+
+/// Draws the pixels with `#` (lit) and `.` (dark), one line per row.
+pub fn render(pixels: &[Vec<bool>]) -> String {
+    let rows: Vec<String> = pixels
+        .iter()
+        .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+        .collect();
+
+    rows.join("\n")
+}
+
+/// Reads the letters drawn in `pixels`, `None` when the drawing isn't made of known letters.
+///
+/// Empty rows around the letters are ignored, the height that remains picks the font.
+pub fn decode(pixels: &[Vec<bool>]) -> Option<String> {
+    let first = pixels.iter().position(|row| row.contains(&true))?;
+    let last = pixels.iter().rposition(|row| row.contains(&true))?;
+
+    let rows = &pixels[first..=last];
+
+    let font = match rows.len() {
+        6 => SMALL_FONT,
+        10 => LARGE_FONT,
+        _ => return None,
+    };
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+    let lit_column = |column: usize| rows.iter().any(|row| row.get(column) == Some(&true));
+
+    let mut text = String::new();
+    let mut column = 0;
+
+    while column < width {
+        if !lit_column(column) {
+            column += 1;
+            continue;
+        }
+
+        // letters are separated by at least one dark column
+        let start = column;
+
+        while column < width && lit_column(column) {
+            column += 1;
+        }
+
+        let glyph: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                (start..column)
+                    .map(|x| if row.get(x) == Some(&true) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+
+        let &(letter, _) = font.iter().find(|&&(_, art)| art == glyph.as_slice())?;
+
+        text.push(letter);
+    }
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::ocr::{decode, parse_art, render};
+
+    #[test]
+    fn small_font() {
+        let pixels = parse_art(
+            "\
+            ####.###....##.###..###..#..#.#..#.#..#\n\
+            #....#..#....#.#..#.#..#.#.#..#..#.#..#\n\
+            ###..#..#....#.###..#..#.##...####.#..#\n\
+            #....###.....#.#..#.###..#.#..#..#.#..#\n\
+            #....#....#..#.#..#.#.#..#.#..#..#.#..#\n\
+            ####.#.....##..###..#..#.#..#.#..#..##.",
+        );
+
+        assert_eq!(decode(&pixels), Some("EPJBRKHU".to_owned()));
+    }
+
+    #[test]
+    fn large_font() {
+        let pixels = parse_art(
+            "\
+            #....#..######\n\
+            #....#.......#\n\
+            .#..#........#\n\
+            .#..#.......#.\n\
+            ..##.......#..\n\
+            ..##......#...\n\
+            .#..#....#....\n\
+            .#..#...#.....\n\
+            #....#..#.....\n\
+            #....#..######",
+        );
+
+        assert_eq!(decode(&pixels), Some("XZ".to_owned()));
+    }
+
+    #[test]
+    fn padding_is_ignored() {
+        let pixels = parse_art(
+            "\
+            ..........\n\
+            ..#..#.###\n\
+            ..#..#..#.\n\
+            ..####..#.\n\
+            ..#..#..#.\n\
+            ..#..#..#.\n\
+            ..#..#.###\n\
+            ..........",
+        );
+
+        assert_eq!(decode(&pixels), Some("HI".to_owned()));
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(decode(&parse_art("#\n#\n#")), None);
+        assert_eq!(decode(&parse_art("....\n....")), None);
+    }
+
+    #[test]
+    fn render_round_trips() {
+        let art = ".##.\n#..#";
+
+        assert_eq!(render(&parse_art(art)), art);
+    }
+}
@@ -0,0 +1,224 @@
+use serde::Serialize;
+
+use crate::answers::Verification;
+use crate::cli::Format;
+use crate::report::RunReport;
+use crate::runner::{Outcome, PartResult};
+use crate::shared::{Note, PartSolution};
+use crate::timing::format_duration;
+
+/// One part's result as it is written in the `json` and `ndjson` formats.
+#[derive(Serialize, Debug)]
+pub struct Record {
+    #[serde(flatten)]
+    pub report: RunReport,
+    pub verification: Option<String>,
+    pub expected: Option<PartSolution>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+}
+
+impl Record {
+    pub fn new(mut result: PartResult, verification: Option<&Verification>) -> Self {
+        let notes = std::mem::take(&mut result.notes);
+
+        Record {
+            report: RunReport::from(result),
+            notes,
+            verification: verification.map(|v| v.to_string().to_lowercase()),
+            expected: match verification {
+                Some(&Verification::Fail { ref expected }) => Some(expected.clone()),
+                Some(&(Verification::Pass | Verification::Missing | Verification::Error))
+                | None => None,
+            },
+        }
+    }
+}
+
+/// Writes results in the chosen format, `json` is buffered until [`Printer::finish`].
+pub struct Printer {
+    format: Format,
+    records: Vec<Record>,
+}
+
+impl Printer {
+    pub fn new(format: Format) -> Self {
+        Printer {
+            format,
+            records: Vec::new(),
+        }
+    }
+
+    pub fn print(&mut self, result: PartResult, verification: Option<&Verification>) {
+        match self.format {
+            Format::Text => {
+                print_text(&result);
+
+                for note in &result.notes {
+                    println!("    {}", note);
+                }
+            },
+            Format::Ndjson => println!(
+                "{}",
+                serde_json::to_string(&Record::new(result, verification))
+                    .expect("Records always serialize")
+            ),
+            Format::Json => self.records.push(Record::new(result, verification)),
+        }
+    }
+
+    pub fn finish(self) {
+        if matches!(self.format, Format::Json) {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&self.records).expect("Records always serialize")
+            );
+        }
+    }
+}
+
+/// The line of a part's result in the `text` format.
+fn print_text(result: &PartResult) {
+    match result.outcome {
+        Outcome::Solved(ref answer) => println!(
+            "Answer to Day {} ({}), part {} is ... {} ({})",
+            result.day, result.title, result.part, answer, result.timing
+        ),
+        Outcome::Failed(ref message) => println!(
+            "Day {} ({}), part {} failed: {}",
+            result.day, result.title, result.part, message
+        ),
+        Outcome::Panicked(ref message) => println!(
+            "Day {} ({}), part {} panicked: {}",
+            result.day, result.title, result.part, message
+        ),
+        Outcome::TimedOut(timeout) => println!(
+            "Day {} ({}), part {} timed out after {}",
+            result.day,
+            result.title,
+            result.part,
+            format_duration(timeout)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use num_bigint::BigInt;
+    use pretty_assertions::assert_eq;
+
+    use crate::answers::Verification;
+    use crate::output::Record;
+    use crate::runner::{Outcome, PartResult};
+    use crate::shared::{Note, Part, PartSolution};
+    use crate::timing::Timing;
+
+    fn result(outcome: Outcome) -> PartResult {
+        PartResult {
+            day: 7,
+            title: "Handy Haversacks",
+            part: Part::Puzzle(2),
+            input_name: Arc::from("input"),
+            outcome,
+            notes: Vec::new(),
+            timing: Timing {
+                parse: None,
+                solve: Duration::from_nanos(1_500),
+            },
+        }
+    }
+
+    #[test]
+    fn number() {
+        let record = Record::new(result(Outcome::Solved(PartSolution::U32(172_246))), None);
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":7,"part":2,"input":"input","answer":{"type":"U32","value":172246},"status":"solved","parse_ns":null,"solve_ns":1500,"verification":null,"expected":null}"#
+        );
+    }
+
+    #[test]
+    fn notes() {
+        let record = Record::new(
+            PartResult {
+                notes: vec![Note::new("Flipped an instruction").with("line", 4)],
+                ..result(Outcome::Solved(PartSolution::ISize(8)))
+            },
+            None,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":7,"part":2,"input":"input","answer":{"type":"ISize","value":8},"status":"solved","parse_ns":null,"solve_ns":1500,"verification":null,"expected":null,"notes":[{"step":"Flipped an instruction","values":[["line","4"]]}]}"#
+        );
+    }
+
+    #[test]
+    fn big_numbers() {
+        let record = Record::new(result(Outcome::Solved(PartSolution::U128(u128::MAX))), None);
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":7,"part":2,"input":"input","answer":{"type":"U128","value":"340282366920938463463374607431768211455"},"status":"solved","parse_ns":null,"solve_ns":1500,"verification":null,"expected":null}"#
+        );
+
+        let big = BigInt::from(u128::MAX) * 10;
+
+        let record = Record::new(result(Outcome::Solved(PartSolution::BigInt(big))), None);
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":7,"part":2,"input":"input","answer":{"type":"BigInt","value":"3402823669209384634633746074317682114550"},"status":"solved","parse_ns":null,"solve_ns":1500,"verification":null,"expected":null}"#
+        );
+    }
+
+    #[test]
+    fn vec_is_an_array() {
+        let record = Record::new(
+            result(Outcome::Solved(PartSolution::Vec(vec![
+                "#..#".to_owned(),
+                ".##.".to_owned(),
+            ]))),
+            Some(&Verification::Fail {
+                expected: PartSolution::Vec(vec!["#..#".to_owned(), "#.#.".to_owned()]),
+            }),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r##"{"day":7,"part":2,"input":"input","answer":{"type":"Vec","value":["#..#",".##."]},"status":"solved","parse_ns":null,"solve_ns":1500,"verification":"fail","expected":{"type":"Vec","value":["#..#","#.#."]}}"##
+        );
+    }
+
+    #[test]
+    fn panicked() {
+        let record = Record::new(
+            result(Outcome::Panicked("Application ended".to_owned())),
+            Some(&Verification::Error),
+        );
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":7,"part":2,"input":"input","answer":null,"status":"panicked","message":"Application ended","parse_ns":null,"solve_ns":1500,"verification":"error","expected":null}"#
+        );
+    }
+
+    #[test]
+    fn failed() {
+        let record = Record::new(
+            result(Outcome::Failed(
+                "Failed to parse the input of day 7: There is no shiny gold bag".to_owned(),
+            )),
+            None,
+        );
+
+        assert_eq!(
+            serde_json::to_string(&record).unwrap(),
+            r#"{"day":7,"part":2,"input":"input","answer":null,"status":"failed","message":"Failed to parse the input of day 7: There is no shiny gold bag","parse_ns":null,"solve_ns":1500,"verification":null,"expected":null}"#
+        );
+    }
+}
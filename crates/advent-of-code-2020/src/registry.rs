@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Index;
+
+use color_eyre::eyre::bail;
+
+use crate::shared::DynDay;
+
+/// A day's solution, registered under the day number its metadata declares.
+pub struct Registration {
+    pub module: &'static str,
+    pub solution: Box<dyn DynDay>,
+}
+
+/// Declares the given day modules and generates `registrations()`, which registers each module's `Solution` under
+/// the day of its own metadata.
+macro_rules! register_days {
+    ($($module:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        fn registrations() -> Vec<$crate::registry::Registration> {
+            vec![$(
+                $crate::registry::Registration {
+                    module: stringify!($module),
+                    solution: Box::new($module::Solution {}),
+                },
+            )*]
+        }
+    };
+}
+
+pub(crate) use register_days;
+
+/// Every registered solution, by day.
+pub struct Registry {
+    registrations: BTreeMap<u32, Registration>,
+}
+
+impl Registry {
+    /// Collects the registrations, two modules claiming the same day is an error.
+    pub fn new(registrations: Vec<Registration>) -> Result<Self, color_eyre::Report> {
+        let mut by_day = BTreeMap::<u32, Registration>::new();
+
+        for registration in registrations {
+            let day = registration.solution.metadata().day;
+
+            if day == 0 {
+                bail!("{} is registered as day 0", registration.module);
+            }
+
+            if let Some(existing) = by_day.get(&day) {
+                bail!(
+                    "Day {} is registered by both {} and {}",
+                    day,
+                    existing.module,
+                    registration.module
+                );
+            }
+
+            by_day.insert(day, registration);
+        }
+
+        Ok(Registry {
+            registrations: by_day,
+        })
+    }
+
+    pub fn days(&self) -> BTreeSet<u32> {
+        self.registrations.keys().copied().collect()
+    }
+
+    /// The days before the last registered day that don't have a solution.
+    pub fn gaps(&self) -> Vec<u32> {
+        let last = self.registrations.keys().next_back().copied().unwrap_or(0);
+
+        (1..last)
+            .filter(|day| !self.registrations.contains_key(day))
+            .collect()
+    }
+}
+
+impl Index<u32> for Registry {
+    type Output = dyn DynDay;
+
+    fn index(&self, index: u32) -> &Self::Output {
+        &*self.registrations[&index].solution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::registry::{Registration, Registry};
+    use crate::shared::{FakeDay, PartSolution};
+
+    fn registration(day: u32, module: &'static str) -> Registration {
+        Registration {
+            module,
+            solution: Box::new(FakeDay::new(
+                day,
+                |_| Ok(()),
+                |&(), _| Ok(PartSolution::U32(1)),
+                |&(), _| Ok(PartSolution::U32(2)),
+            )),
+        }
+    }
+
+    #[test]
+    fn registered_out_of_order() {
+        let registry = Registry::new(vec![
+            registration(3, "day_03"),
+            registration(1, "day_01"),
+            registration(2, "day_02"),
+        ])
+        .unwrap();
+
+        assert_eq!(registry.days(), BTreeSet::from([1, 2, 3]));
+        assert_eq!(registry.gaps(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn gaps() {
+        let registry = Registry::new(vec![
+            registration(1, "day_01"),
+            registration(4, "day_04"),
+            registration(6, "day_06"),
+        ])
+        .unwrap();
+
+        assert_eq!(registry.gaps(), vec![2, 3, 5]);
+    }
+
+    #[test]
+    fn duplicate_day() {
+        let error = Registry::new(vec![registration(7, "day_07"), registration(7, "day_08")])
+            .err()
+            .unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "Day 7 is registered by both day_07 and day_08"
+        );
+    }
+
+    #[test]
+    fn day_zero() {
+        let error = Registry::new(vec![registration(0, "day_00")])
+            .err()
+            .unwrap();
+
+        assert_eq!(error.to_string(), "day_00 is registered as day 0");
+    }
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+use crate::runner::{Outcome, PartResult};
+use crate::shared::{Part, PartSolution};
+
+/// How a part ended, its answer is kept in [`RunReport::answer`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Status {
+    Solved,
+    Failed { message: String },
+    Panicked { message: String },
+    TimedOut { timeout_ns: u64 },
+}
+
+/// One part's result, in the schema that is shared by the `json` and `ndjson` output and anything else that stores
+/// results.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RunReport {
+    pub day: u32,
+    pub part: Part,
+    pub input: String,
+    pub answer: Option<PartSolution>,
+    #[serde(flatten)]
+    pub status: Status,
+    pub parse_ns: Option<u64>,
+    pub solve_ns: u64,
+}
+
+fn nanos(duration: std::time::Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl From<PartResult> for RunReport {
+    fn from(result: PartResult) -> Self {
+        let (answer, status) = match result.outcome {
+            Outcome::Solved(answer) => (Some(answer), Status::Solved),
+            Outcome::Failed(message) => (None, Status::Failed { message }),
+            Outcome::Panicked(message) => (None, Status::Panicked { message }),
+            Outcome::TimedOut(timeout) => (
+                None,
+                Status::TimedOut {
+                    timeout_ns: nanos(timeout),
+                },
+            ),
+        };
+
+        RunReport {
+            day: result.day,
+            part: result.part,
+            input: result.input_name.to_string(),
+            answer,
+            status,
+            parse_ns: result.timing.parse.map(nanos),
+            solve_ns: nanos(result.timing.solve),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use pretty_assertions::assert_eq;
+
+    use crate::ocr::parse_art;
+    use crate::report::{RunReport, Status};
+    use crate::shared::{Part, PartSolution};
+
+    fn every_variant() -> Vec<PartSolution> {
+        vec![
+            PartSolution::I32(-1_019_571),
+            PartSolution::U32(1_019_571),
+            PartSolution::I64(i64::MIN),
+            PartSolution::U64(u64::MAX),
+            PartSolution::I128(i128::MIN),
+            PartSolution::U128(u128::MAX),
+            PartSolution::ISize(-920),
+            PartSolution::USize(620),
+            PartSolution::BigInt(BigInt::from(u128::MAX) * 1_000),
+            PartSolution::String("mxmxvkd,sqjhc,fvjkl".to_owned()),
+            PartSolution::Vec(vec!["#..#".to_owned(), ".##.".to_owned()]),
+            PartSolution::Grid(parse_art("#..#.###\n#..#..#.")),
+            PartSolution::None,
+        ]
+    }
+
+    fn report(answer: PartSolution) -> RunReport {
+        RunReport {
+            day: 13,
+            part: Part::Puzzle(2),
+            input: "input".to_owned(),
+            answer: Some(answer),
+            status: Status::Solved,
+            parse_ns: Some(2_000),
+            solve_ns: 1_500,
+        }
+    }
+
+    /// The variant's name, which `PartialEq` ignores.
+    fn variant_name(answer: &PartSolution) -> &'static str {
+        match *answer {
+            PartSolution::I32(_) => "I32",
+            PartSolution::U32(_) => "U32",
+            PartSolution::I64(_) => "I64",
+            PartSolution::U64(_) => "U64",
+            PartSolution::I128(_) => "I128",
+            PartSolution::U128(_) => "U128",
+            PartSolution::ISize(_) => "ISize",
+            PartSolution::USize(_) => "USize",
+            PartSolution::BigInt(_) => "BigInt",
+            PartSolution::String(_) => "String",
+            PartSolution::Vec(_) => "Vec",
+            PartSolution::Grid(_) => "Grid",
+            PartSolution::None => "None",
+        }
+    }
+
+    /// Compares the variants too, `PartialEq` considers `U32(5)` and `USize(5)` equal.
+    fn assert_identical(left: &RunReport, right: &RunReport) {
+        assert_eq!(left, right);
+        assert_eq!(
+            left.answer.as_ref().map(variant_name),
+            right.answer.as_ref().map(variant_name)
+        );
+    }
+
+    #[test]
+    fn json_round_trips() {
+        for answer in every_variant() {
+            let report = report(answer);
+
+            let json = serde_json::to_string(&report).unwrap();
+
+            assert_identical(&serde_json::from_str::<RunReport>(&json).unwrap(), &report);
+        }
+    }
+
+    #[test]
+    fn toml_round_trips() {
+        for answer in every_variant() {
+            let report = report(answer);
+
+            let toml = toml::to_string(&report).unwrap();
+
+            assert_identical(&toml::from_str::<RunReport>(&toml).unwrap(), &report);
+        }
+    }
+
+    #[test]
+    fn failures_round_trip() {
+        let failures = [
+            Status::Failed {
+                message: "There is no shiny gold bag".to_owned(),
+            },
+            Status::Panicked {
+                message: "Application ended".to_owned(),
+            },
+            Status::TimedOut {
+                timeout_ns: 10_000_000,
+            },
+        ];
+
+        for status in failures {
+            let report = RunReport {
+                answer: None,
+                status,
+                parse_ns: None,
+                ..report(PartSolution::None)
+            };
+
+            let json = serde_json::to_string(&report).unwrap();
+            let toml = toml::to_string(&report).unwrap();
+
+            assert_eq!(serde_json::from_str::<RunReport>(&json).unwrap(), report);
+            assert_eq!(toml::from_str::<RunReport>(&toml).unwrap(), report);
+        }
+    }
+
+    #[test]
+    fn schema() {
+        assert_eq!(
+            serde_json::to_string(&report(PartSolution::U128(u128::MAX))).unwrap(),
+            r#"{"day":13,"part":2,"input":"input","answer":{"type":"U128","value":"340282366920938463463374607431768211455"},"status":"solved","parse_ns":2000,"solve_ns":1500}"#
+        );
+
+        assert_eq!(
+            toml::to_string(&PartSolution::USize(620)).unwrap(),
+            "type = \"USize\"\nvalue = 620\n"
+        );
+    }
+
+    #[test]
+    fn unknown_variant() {
+        let error = serde_json::from_str::<PartSolution>(r#"{"type":"U16","value":5}"#)
+            .unwrap_err()
+            .to_string();
+
+        assert!(
+            error.starts_with("unknown variant `U16`, expected one of `I32`, `U32`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let error = serde_json::from_str::<PartSolution>(r#"{"type":"U32","value":-1}"#)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.starts_with("-1 does not fit in u32"), "{}", error);
+    }
+}
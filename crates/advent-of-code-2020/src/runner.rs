@@ -0,0 +1,730 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock, mpsc};
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{WrapErr as _, bail};
+
+use crate::cli::{ParamOverride, Selection};
+use crate::registry::Registry;
+use crate::shared::{
+    Cancellation, Context, DynDay, Explain, Frame, MAIN_IMPLEMENTATION, Note, Param, ParsedInput,
+    Part, PartSolution,
+};
+use crate::timing::{Timing, format_duration, time};
+
+#[derive(Clone)]
+pub enum Outcome {
+    Solved(PartSolution),
+    /// The solution returned an error, rendered with its causes.
+    Failed(String),
+    Panicked(String),
+    /// The part ran for longer than its time budget.
+    TimedOut(Duration),
+}
+
+impl Outcome {
+    /// The answer of a part, or why it has none.
+    pub fn describe(&self) -> String {
+        match *self {
+            Outcome::Solved(ref answer) => answer.answer_text().escape_debug().to_string(),
+            Outcome::Failed(ref message) => format!("failed: {}", message),
+            Outcome::Panicked(ref message) => format!("panicked: {}", message),
+            Outcome::TimedOut(timeout) => format!("timed out after {}", format_duration(timeout)),
+        }
+    }
+}
+
+pub struct PartResult {
+    pub day: u32,
+    pub title: &'static str,
+    pub part: Part,
+    pub input_name: Arc<str>,
+    pub outcome: Outcome,
+    /// How the part reached its answer, empty unless it was asked to explain.
+    pub notes: Vec<Note>,
+    pub timing: Timing,
+}
+
+/// A day's input, parsed once by whichever of the day's parts gets to it first.
+pub struct DayInput {
+    /// The input text, or why it couldn't be loaded.
+    text: Result<Arc<str>, String>,
+    parsed: OnceLock<(Result<ParsedInput, Outcome>, Duration)>,
+}
+
+impl DayInput {
+    pub fn new(text: Result<Arc<str>, String>) -> Self {
+        Self {
+            text,
+            parsed: OnceLock::new(),
+        }
+    }
+}
+
+/// A single part of a single day, together with the input to solve it for.
+pub struct Job<'s> {
+    pub day: u32,
+    pub part: Part,
+    pub input_name: Arc<str>,
+    pub input: Arc<DayInput>,
+    pub solution: &'s dyn DynDay,
+    /// Which of the part's implementations solves it.
+    pub implementation: &'static str,
+    pub timeout: Option<Duration>,
+    /// The day's parameter overrides, by name.
+    pub params: Arc<BTreeMap<String, String>>,
+    /// Whether the part writes down how it reached its answer.
+    pub explain: bool,
+}
+
+impl<'s> Job<'s> {
+    /// The same job, solved by another of the part's implementations.
+    pub fn with_implementation(&self, implementation: &'static str) -> Job<'s> {
+        Job {
+            day: self.day,
+            part: self.part.clone(),
+            input_name: Arc::clone(&self.input_name),
+            input: Arc::clone(&self.input),
+            solution: self.solution,
+            implementation,
+            timeout: self.timeout,
+            params: Arc::clone(&self.params),
+            explain: self.explain,
+        }
+    }
+
+    /// Parses the input from scratch, an error or panic in the solution is returned as the outcome of the part.
+    pub fn parse(&self) -> (Result<ParsedInput, Outcome>, Duration) {
+        let text = match self.input.text {
+            Ok(ref text) => text,
+            Err(ref message) => return (Err(Outcome::Failed(message.clone())), Duration::ZERO),
+        };
+
+        time(|| {
+            attempt(|| {
+                self.solution
+                    .parse(text)
+                    .wrap_err_with(|| format!("Failed to parse the input of day {}", self.day))
+            })
+        })
+    }
+
+    /// Solves the part for an input this job's `parse` returned, within the job's time budget.
+    pub fn solve_parsed(&self, parsed: &ParsedInput) -> (Outcome, Duration) {
+        let (outcome, solve, _) = self.solve_explained(parsed);
+
+        (outcome, solve)
+    }
+
+    /// Solves the part like `solve_parsed`, also returning the notes the part wrote if the job explains.
+    fn solve_explained(&self, parsed: &ParsedInput) -> (Outcome, Duration, Vec<Note>) {
+        let context = self.context(Cancellation::with_deadline(
+            self.timeout.map(|timeout| Instant::now() + timeout),
+        ));
+
+        let (answer, solve) = time(|| {
+            attempt(|| {
+                self.solution
+                    .solve(&self.part, self.implementation, &**parsed, &context)
+            })
+        });
+
+        let outcome = match (answer, self.timeout) {
+            // whether it gave up or finished anyway, it took too long
+            (_, Some(timeout)) if solve >= timeout => Outcome::TimedOut(timeout),
+            (Ok(answer), _) => Outcome::Solved(answer),
+            (Err(outcome), _) => outcome,
+        };
+
+        (outcome, solve, context.into_notes())
+    }
+
+    fn context(&self, cancellation: Cancellation) -> Context {
+        Context::new(
+            cancellation,
+            Arc::clone(&self.params),
+            if self.explain {
+                Explain::enabled()
+            } else {
+                Explain::default()
+            },
+        )
+    }
+
+    /// Solves the part, parsing the day's input first if no other part of the day has done so yet.
+    pub fn solve(&self) -> PartResult {
+        let &(ref parsed, parse) = self.input.parsed.get_or_init(|| self.parse());
+
+        let (outcome, solve, notes) = match *parsed {
+            Ok(ref parsed) => self.solve_explained(parsed),
+            Err(ref outcome) => (outcome.clone(), Duration::ZERO, Vec::new()),
+        };
+
+        self.result(outcome, notes, parse, solve)
+    }
+
+    /// Solves the part like `solve`, handing the frames of the solution to `frames`.
+    ///
+    /// The time budget doesn't apply, the time it takes to show the frames would count against it.
+    pub fn visualize(&self, frames: &mut dyn FnMut(Frame)) -> PartResult {
+        let &(ref parsed, parse) = self.input.parsed.get_or_init(|| self.parse());
+
+        let (outcome, solve, notes) = match *parsed {
+            Ok(ref parsed) => {
+                let context = self.context(Cancellation::default());
+
+                let (answer, solve) = time(|| {
+                    attempt(|| {
+                        self.solution
+                            .visualize(&self.part, &**parsed, &context, &mut *frames)
+                    })
+                });
+
+                (
+                    answer.map_or_else(|outcome| outcome, Outcome::Solved),
+                    solve,
+                    context.into_notes(),
+                )
+            },
+            Err(ref outcome) => (outcome.clone(), Duration::ZERO, Vec::new()),
+        };
+
+        self.result(outcome, notes, parse, solve)
+    }
+
+    fn result(
+        &self,
+        outcome: Outcome,
+        notes: Vec<Note>,
+        parse: Duration,
+        solve: Duration,
+    ) -> PartResult {
+        PartResult {
+            day: self.day,
+            title: self.solution.metadata().title,
+            part: self.part.clone(),
+            input_name: Arc::clone(&self.input_name),
+            outcome,
+            notes,
+            timing: Timing {
+                parse: Some(parse),
+                solve,
+            },
+        }
+    }
+}
+
+/// Runs `f`, turning an error or a panic into the outcome of the part.
+fn attempt<T, F>(f: F) -> Result<T, Outcome>
+where
+    F: FnOnce() -> Result<T, color_eyre::Report>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(report)) => Err(Outcome::Failed(format!("{:#}", report))),
+        Err(payload) => Err(Outcome::Panicked(panic_message(&*payload))),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
+
+/// Resolves the selection into the jobs to run, in day and part order, loading every day's input once.
+pub fn jobs<'s>(
+    solutions: &'s Registry,
+    selection: &Selection,
+) -> Result<Vec<Job<'s>>, color_eyre::Report> {
+    let days = selection.selected_days(&solutions.days())?;
+    let input_source = selection.input_source();
+
+    if input_source.is_single_input() && days.len() != 1 {
+        bail!("`--input` can only be used when selecting a single day");
+    }
+
+    let input_name: Arc<str> = input_source.name().into();
+
+    if let Some(ref part) = selection.part
+        && !days
+            .iter()
+            .any(|&day| solutions[day].parts().contains(part))
+    {
+        bail!("None of the selected days has a part {}", part);
+    }
+
+    for param in &selection.params {
+        let has_param = |day: &u32| {
+            solutions[*day]
+                .params()
+                .iter()
+                .any(|declared| declared.name == param.name)
+        };
+
+        if param.day.is_none() && !days.iter().any(has_param) {
+            bail!("None of the selected days has a parameter `{}`", param.name);
+        }
+    }
+
+    let mut jobs = Vec::new();
+
+    for day in days {
+        let solution = &solutions[day];
+        let params = Arc::new(day_params(&selection.params, day, solution.params())?);
+        // a day without input fails its parts, the other days still run
+        let input = Arc::new(DayInput::new(
+            input_source
+                .load(day, solution.embedded_input())
+                .map(Into::into)
+                .map_err(|report| format!("{:#}", report)),
+        ));
+
+        for part in solution.parts() {
+            if selection.runs_part(&part) {
+                let timeout = selection.timeout(day, &part);
+
+                jobs.push(Job {
+                    day,
+                    part,
+                    input_name: Arc::clone(&input_name),
+                    input: Arc::clone(&input),
+                    solution,
+                    implementation: MAIN_IMPLEMENTATION,
+                    timeout,
+                    params: Arc::clone(&params),
+                    explain: selection.explain,
+                });
+            }
+        }
+    }
+
+    Ok(jobs)
+}
+
+/// The `--param` values that apply to `day`, naming this day for a parameter it doesn't have is an error.
+pub fn day_params(
+    overrides: &[ParamOverride],
+    day: u32,
+    declared: &[Param],
+) -> Result<BTreeMap<String, String>, color_eyre::Report> {
+    let mut params = BTreeMap::new();
+
+    for param in overrides {
+        if param.day.is_some_and(|only| only != day) {
+            continue;
+        }
+
+        if declared.iter().any(|declared| declared.name == param.name) {
+            params.insert(param.name.clone(), param.value.clone());
+        } else if param.day.is_some() {
+            let names: Vec<&str> = declared.iter().map(|declared| declared.name).collect();
+
+            bail!(
+                "Day {} has no parameter `{}`, it has {}",
+                day,
+                param.name,
+                if names.is_empty() {
+                    "none".to_owned()
+                } else {
+                    names.join(", ")
+                }
+            );
+        } else {
+            // it's meant for one of the other selected days
+        }
+    }
+
+    Ok(params)
+}
+
+/// Runs `work` for every job on up to `threads` threads, handing the results to `on_result` in job order.
+pub fn schedule<'s, T, W, F>(jobs: &[Job<'s>], threads: NonZeroUsize, work: W, mut on_result: F)
+where
+    T: Send,
+    W: Fn(&Job<'s>) -> T + Sync,
+    F: FnMut(T),
+{
+    let next_job = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads.get().min(jobs.len()) {
+            let sender = sender.clone();
+            let next_job = &next_job;
+            let work = &work;
+
+            scope.spawn(move || {
+                loop {
+                    let index = next_job.fetch_add(1, Ordering::Relaxed);
+
+                    let Some(job) = jobs.get(index) else {
+                        break;
+                    };
+
+                    if sender.send((index, work(job))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        drop(sender);
+
+        // results arrive in completion order, hold them back until all earlier jobs are done
+        let mut pending = BTreeMap::new();
+        let mut waiting_for = 0;
+
+        for (index, result) in receiver {
+            pending.insert(index, result);
+
+            while let Some(result) = pending.remove(&waiting_for) {
+                on_result(result);
+
+                waiting_for += 1;
+            }
+        }
+    });
+}
+
+/// Solves every selected part of every selected day, handing the results to `on_result` in day and part order.
+pub fn run<F>(
+    solutions: &Registry,
+    selection: &Selection,
+    on_result: F,
+) -> Result<(), color_eyre::Report>
+where
+    F: FnMut(PartResult),
+{
+    let jobs = jobs(solutions, selection)?;
+
+    schedule(&jobs, selection.jobs, Job::solve, on_result);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use clap::Parser as _;
+    use color_eyre::eyre::bail;
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::Cli;
+    use crate::registry::{Registration, Registry};
+    use crate::runner::{Outcome, run};
+    use crate::shared::{BonusPart, FakeDay, Param, Part, PartSolution};
+
+    /// A day whose first part sleeps less the later the day is, and whose second part fails on even days and panics
+    /// on odd ones.
+    fn sleepy(day: u32) -> FakeDay<()> {
+        FakeDay::new(
+            day,
+            |_| Ok(()),
+            move |&(), _| {
+                // later days finish first
+                std::thread::sleep(std::time::Duration::from_millis(u64::from(10 - day)));
+
+                Ok(PartSolution::U32(day))
+            },
+            move |&(), _| {
+                if day.is_multiple_of(2) {
+                    bail!("Day {} has no part 2", day);
+                }
+
+                panic!("Day {} has no part 2", day)
+            },
+        )
+    }
+
+    /// A day that counts its input's lines, and how often it was parsed in `parses`.
+    fn counting(parses: &Arc<AtomicUsize>) -> FakeDay<usize> {
+        let parses = Arc::clone(parses);
+
+        FakeDay::new(
+            1,
+            move |input| {
+                parses.fetch_add(1, Ordering::Relaxed);
+
+                if input.is_empty() {
+                    bail!("The input is empty");
+                }
+
+                Ok(input.lines().count())
+            },
+            |&input, _| Ok(PartSolution::USize(input)),
+            |&input, _| Ok(PartSolution::USize(input * 2)),
+        )
+        .with_embedded_input("1\n2\n3")
+    }
+
+    const FACTOR: Param = Param {
+        name: "factor",
+        default: "2",
+        description: "What part 2 multiplies the number with.",
+    };
+
+    fn tunable() -> FakeDay<usize> {
+        FakeDay::new(
+            1,
+            |input| Ok(input.parse()?),
+            |&input, _| Ok(PartSolution::USize(input)),
+            |&input, context| {
+                Ok(PartSolution::USize(
+                    input * context.param::<usize>(&FACTOR)?,
+                ))
+            },
+        )
+        .with_embedded_input("3")
+        .with_params(&[FACTOR])
+        .with_bonus_parts(&[BonusPart {
+            name: "squared",
+            solve: |_, input, _| Ok(PartSolution::USize(input * input)),
+        }])
+    }
+
+    fn solutions() -> Registry {
+        Registry::new(
+            (1..=5)
+                .map(|day| Registration {
+                    module: "sleepy",
+                    solution: Box::new(sleepy(day)),
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn results_are_in_order_and_failures_are_caught() {
+        let cli = Cli::try_parse_from(["aoc", "--jobs", "4"]).unwrap();
+
+        assert_eq!(cli.selection.jobs, NonZeroUsize::new(4).unwrap());
+
+        let mut results = Vec::new();
+
+        run(&solutions(), &cli.selection, |result| {
+            let outcome = match result.outcome {
+                Outcome::Solved(answer) => answer.to_string(),
+                Outcome::Failed(message) => format!("failed: {}", message),
+                Outcome::Panicked(message) => format!("panicked: {}", message),
+                Outcome::TimedOut(timeout) => format!("timed out after {:?}", timeout),
+            };
+
+            results.push((result.day, result.part, outcome));
+        })
+        .unwrap();
+
+        let expected: Vec<_> = (1..=5)
+            .flat_map(|day| {
+                [
+                    (day, Part::Puzzle(1), day.to_string()),
+                    (
+                        day,
+                        Part::Puzzle(2),
+                        format!(
+                            "{}: Day {} has no part 2",
+                            if day % 2 == 0 { "failed" } else { "panicked" },
+                            day
+                        ),
+                    ),
+                ]
+            })
+            .collect();
+
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn input_is_parsed_once_per_day() {
+        let cli = Cli::try_parse_from(["aoc", "--jobs", "2"]).unwrap();
+
+        let parses = Arc::new(AtomicUsize::new(0));
+
+        let registry = Registry::new(vec![Registration {
+            module: "counting",
+            solution: Box::new(counting(&parses)),
+        }])
+        .unwrap();
+
+        let mut results = Vec::new();
+
+        run(&registry, &cli.selection, |result| {
+            assert!(result.timing.parse.is_some());
+
+            if let Outcome::Solved(answer) = result.outcome {
+                results.push(answer);
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            results,
+            vec![PartSolution::USize(3), PartSolution::USize(6)]
+        );
+        assert_eq!(parses.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn parse_errors_fail_every_part() {
+        let dir = std::env::temp_dir().join(format!("aoc-2020-empty-{}", std::process::id()));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day_01.txt"), "").unwrap();
+
+        let cli = Cli::try_parse_from(["aoc", "--inputs-dir", dir.to_str().unwrap()]).unwrap();
+
+        let registry = Registry::new(vec![Registration {
+            module: "counting",
+            solution: Box::new(counting(&Arc::default())),
+        }])
+        .unwrap();
+
+        let mut results = Vec::new();
+
+        run(&registry, &cli.selection, |result| {
+            if let Outcome::Failed(message) = result.outcome {
+                results.push(message);
+            }
+        })
+        .unwrap();
+
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(
+            results,
+            vec!["Failed to parse the input of day 1: The input is empty"; 2]
+        );
+    }
+
+    #[test]
+    fn slow_parts_time_out() {
+        let cli = Cli::try_parse_from(["aoc", "--jobs", "2", "--timeout", "10ms"]).unwrap();
+
+        let registry = Registry::new(vec![Registration {
+            module: "spinning",
+            solution: Box::new(FakeDay::new(
+                1,
+                |_| Ok(()),
+                |&(), context| loop {
+                    context.cancellation().check()?;
+                },
+                |&(), _| Ok(PartSolution::U32(2)),
+            )),
+        }])
+        .unwrap();
+
+        let mut results = Vec::new();
+
+        run(&registry, &cli.selection, |result| {
+            results.push((result.part, result.outcome));
+        })
+        .unwrap();
+
+        assert!(matches!(
+            results.as_slice(),
+            [
+                (Part::Puzzle(1), Outcome::TimedOut(timeout)),
+                (Part::Puzzle(2), Outcome::Solved(PartSolution::U32(2)))
+            ] if *timeout == Duration::from_millis(10)
+        ));
+    }
+
+    #[test]
+    fn params_are_overridden() {
+        let registry = Registry::new(vec![Registration {
+            module: "tunable",
+            solution: Box::new(tunable()),
+        }])
+        .unwrap();
+
+        let solve = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).unwrap();
+
+            let mut results = Vec::new();
+
+            run(&registry, &cli.selection, |result| {
+                if let Outcome::Solved(answer) = result.outcome {
+                    results.push(answer);
+                }
+            })
+            .map(|()| results)
+        };
+
+        assert_eq!(
+            solve(&["aoc", "--param", "factor=5"]).unwrap(),
+            vec![PartSolution::USize(3), PartSolution::USize(15)]
+        );
+        assert_eq!(
+            solve(&["aoc", "--param", "1:factor=4"]).unwrap(),
+            vec![PartSolution::USize(3), PartSolution::USize(12)]
+        );
+        assert_eq!(
+            solve(&["aoc", "--param", "factor=x"]).unwrap(),
+            vec![PartSolution::USize(3)]
+        );
+        assert_eq!(
+            solve(&["aoc", "--param", "preamble=5"])
+                .unwrap_err()
+                .to_string(),
+            "None of the selected days has a parameter `preamble`"
+        );
+        assert_eq!(
+            solve(&["aoc", "--param", "1:preamble=5"])
+                .unwrap_err()
+                .to_string(),
+            "Day 1 has no parameter `preamble`, it has factor"
+        );
+    }
+
+    #[test]
+    fn bonus_parts_are_selected() {
+        let registry = Registry::new(vec![Registration {
+            module: "tunable",
+            solution: Box::new(tunable()),
+        }])
+        .unwrap();
+
+        let solve = |args: &[&str]| {
+            let cli = Cli::try_parse_from(args).unwrap();
+
+            let mut results = Vec::new();
+
+            run(&registry, &cli.selection, |result| {
+                if let Outcome::Solved(answer) = result.outcome {
+                    results.push((result.part.to_string(), answer));
+                }
+            })
+            .map(|()| results)
+        };
+
+        assert_eq!(
+            solve(&["aoc", "--bonus"]).unwrap(),
+            vec![
+                ("1".to_owned(), PartSolution::USize(3)),
+                ("2".to_owned(), PartSolution::USize(6)),
+                ("squared".to_owned(), PartSolution::USize(9))
+            ]
+        );
+        assert_eq!(
+            solve(&["aoc", "--part", "squared"]).unwrap(),
+            vec![("squared".to_owned(), PartSolution::USize(9))]
+        );
+        assert_eq!(
+            solve(&["aoc", "--part", "cubed"]).unwrap_err().to_string(),
+            "None of the selected days has a part cubed"
+        );
+    }
+}
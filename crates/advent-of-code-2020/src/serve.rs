@@ -0,0 +1,654 @@
+use std::io::{BufRead, BufReader, ErrorKind, Read as _, Write as _};
+use std::net::{TcpListener, TcpStream};
+use std::num::NonZeroUsize;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+
+use color_eyre::eyre::WrapErr as _;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::cli::ServeArgs;
+use crate::registry::Registry;
+use crate::report::RunReport;
+use crate::runner::{DayInput, Job, Outcome};
+use crate::shared::{MAIN_IMPLEMENTATION, Metadata, Param, Part};
+use crate::timing::format_duration;
+
+/// The most the request line and the headers of a request may take up.
+const MAX_HEAD: u64 = 8 * 1024;
+
+/// What `GET /days` tells about a day.
+#[derive(Serialize)]
+struct Listing {
+    #[serde(flatten)]
+    metadata: Metadata,
+    parts: Vec<Part>,
+    params: &'static [Param],
+}
+
+struct Request {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Status {
+    Ok,
+    BadRequest,
+    NotFound,
+    MethodNotAllowed,
+    RequestTimeout,
+    LengthRequired,
+    ContentTooLarge,
+    UnprocessableContent,
+    HeadersTooLarge,
+    InternalServerError,
+    GatewayTimeout,
+}
+
+impl Status {
+    fn line(self) -> &'static str {
+        match self {
+            Status::Ok => "200 OK",
+            Status::BadRequest => "400 Bad Request",
+            Status::NotFound => "404 Not Found",
+            Status::MethodNotAllowed => "405 Method Not Allowed",
+            Status::RequestTimeout => "408 Request Timeout",
+            Status::LengthRequired => "411 Length Required",
+            Status::ContentTooLarge => "413 Content Too Large",
+            Status::UnprocessableContent => "422 Unprocessable Content",
+            Status::HeadersTooLarge => "431 Request Header Fields Too Large",
+            Status::InternalServerError => "500 Internal Server Error",
+            Status::GatewayTimeout => "504 Gateway Timeout",
+        }
+    }
+}
+
+/// A JSON response.
+struct Response {
+    status: Status,
+    body: String,
+}
+
+impl Response {
+    fn json<T>(status: Status, body: &T) -> Self
+    where
+        T: Serialize,
+    {
+        Response {
+            status,
+            body: serde_json::to_string(body).expect("Responses are plain data"),
+        }
+    }
+
+    fn error<M>(status: Status, message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Response::json(status, &json!({ "error": message.into() }))
+    }
+
+    fn write_to(&self, stream: &mut TcpStream) -> Result<(), std::io::Error> {
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status.line(),
+            self.body.len(),
+            self.body
+        )?;
+
+        stream.flush()
+    }
+}
+
+/// How many connections may be handled at once. A connection holds a [`Permit`] until it's answered, and the part it
+/// solves holds it until it stops running.
+struct Permits {
+    available: Mutex<usize>,
+    returned: Condvar,
+}
+
+impl Permits {
+    fn new(count: NonZeroUsize) -> Arc<Self> {
+        Arc::new(Permits {
+            available: Mutex::new(count.get()),
+            returned: Condvar::new(),
+        })
+    }
+
+    /// Waits until a permit is available and takes it.
+    fn acquire(self: &Arc<Self>) -> Permit {
+        let mut available = self
+            .available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        while *available == 0 {
+            available = self
+                .returned
+                .wait(available)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+
+        *available -= 1;
+
+        Permit(Arc::clone(self))
+    }
+}
+
+/// A taken permit, it's returned when dropped.
+struct Permit(Arc<Permits>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        *self
+            .0
+            .available
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) += 1;
+
+        self.0.returned.notify_one();
+    }
+}
+
+/// Answers requests for the registered days on `args.listen` until the process is stopped.
+pub fn serve(solutions: Registry, args: &ServeArgs) -> Result<(), color_eyre::Report> {
+    let listener = TcpListener::bind(args.listen)
+        .wrap_err_with(|| format!("Failed to listen on {}", args.listen))?;
+
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+
+    // parts that run past their timeout are left running on their own threads, they need the solutions for as long as
+    // the process lives
+    accept(&listener, Box::leak(Box::new(solutions)), args);
+
+    Ok(())
+}
+
+/// Handles every connection that comes in on `listener` on a thread of its own, one request per connection and at most
+/// `args.max_connections` at once. The connections beyond that wait to be accepted.
+fn accept(listener: &TcpListener, solutions: &'static Registry, args: &ServeArgs) {
+    let permits = Permits::new(args.max_connections);
+
+    std::thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let permit = Arc::new(permits.acquire());
+
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || handle(stream, solutions, args, &permit));
+                },
+                Err(error) => eprintln!("Failed to accept a connection: {}", error),
+            }
+        }
+    });
+}
+
+fn handle(
+    mut stream: TcpStream,
+    solutions: &'static Registry,
+    args: &ServeArgs,
+    permit: &Arc<Permit>,
+) {
+    let timeouts = stream
+        .set_read_timeout(Some(args.timeout))
+        .and_then(|()| stream.set_write_timeout(Some(args.timeout)));
+
+    if let Err(error) = timeouts {
+        eprintln!("Failed to set up a connection: {}", error);
+
+        return;
+    }
+
+    let response = match read_request(&stream, args.max_body_size) {
+        Ok(request) => route(solutions, args, &request, permit),
+        Err(response) => response,
+    };
+
+    if let Err(error) = response.write_to(&mut stream) {
+        eprintln!("Failed to respond: {}", error);
+    }
+}
+
+fn read_request(stream: &TcpStream, max_body_size: u64) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream.take(MAX_HEAD));
+
+    let request_line = read_line(&mut reader)?;
+
+    let (method, path) = match *request_line.split_whitespace().collect::<Vec<_>>() {
+        [method, target, version] if version.starts_with("HTTP/") => (
+            method.to_owned(),
+            // the query string doesn't mean anything here
+            target.split('?').next().unwrap_or_default().to_owned(),
+        ),
+        _ => {
+            return Err(Response::error(
+                Status::BadRequest,
+                "Malformed request line",
+            ));
+        },
+    };
+
+    let mut content_length = None;
+
+    loop {
+        let line = read_line(&mut reader)?;
+
+        if line.is_empty() {
+            break;
+        }
+
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(Response::error(Status::BadRequest, "Malformed header"));
+        };
+
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = Some(value.trim().parse::<u64>().map_err(|_| {
+                Response::error(Status::BadRequest, "Content-Length is not a number")
+            })?);
+        } else if name.eq_ignore_ascii_case("transfer-encoding") {
+            return Err(Response::error(
+                Status::LengthRequired,
+                "Send the input with a Content-Length",
+            ));
+        } else {
+            // not needed to answer
+        }
+    }
+
+    let length = match (content_length, method.as_str()) {
+        (Some(length), _) => length,
+        (None, "POST") => {
+            return Err(Response::error(
+                Status::LengthRequired,
+                "Send the input with a Content-Length",
+            ));
+        },
+        (None, _) => 0,
+    };
+
+    if length > max_body_size {
+        return Err(Response::error(
+            Status::ContentTooLarge,
+            format!("The input can be at most {} bytes", max_body_size),
+        ));
+    }
+
+    // part of the body may already be buffered, the rest is still to be read
+    reader.get_mut().set_limit(length);
+
+    let mut body = Vec::new();
+
+    reader
+        .take(length)
+        .read_to_end(&mut body)
+        .map_err(|error| read_error(&error))?;
+
+    if u64::try_from(body.len()).unwrap_or(u64::MAX) < length {
+        return Err(Response::error(
+            Status::BadRequest,
+            "The input is shorter than its Content-Length",
+        ));
+    }
+
+    Ok(Request { method, path, body })
+}
+
+/// Reads a line of the head of the request, without its line ending.
+fn read_line<R>(reader: &mut R) -> Result<String, Response>
+where
+    R: BufRead,
+{
+    let mut line = String::new();
+
+    reader
+        .read_line(&mut line)
+        .map_err(|error| read_error(&error))?;
+
+    if !line.ends_with('\n') {
+        // the head was cut off at `MAX_HEAD`, or the client stopped sending
+        return Err(Response::error(
+            Status::HeadersTooLarge,
+            format!(
+                "The request line and headers can be at most {} bytes",
+                MAX_HEAD
+            ),
+        ));
+    }
+
+    Ok(line.trim_end_matches(['\r', '\n']).to_owned())
+}
+
+fn read_error(error: &std::io::Error) -> Response {
+    let kind = error.kind();
+
+    if matches!(kind, ErrorKind::WouldBlock | ErrorKind::TimedOut) {
+        Response::error(
+            Status::RequestTimeout,
+            "The request took too long to arrive",
+        )
+    } else if kind == ErrorKind::InvalidData {
+        Response::error(Status::BadRequest, "The request is not UTF-8")
+    } else {
+        Response::error(Status::BadRequest, error.to_string())
+    }
+}
+
+fn route(
+    solutions: &'static Registry,
+    args: &ServeArgs,
+    request: &Request,
+    permit: &Arc<Permit>,
+) -> Response {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", &["days"]) => days(solutions),
+        ("POST", &["days", day, "parts", part]) => {
+            solve(solutions, args, day, part, &request.body, permit)
+        },
+        (_, &["days"] | &["days", _, "parts", _]) => Response::error(
+            Status::MethodNotAllowed,
+            format!("{} is not allowed on {}", request.method, request.path),
+        ),
+        _ => Response::error(
+            Status::NotFound,
+            format!(
+                "Nothing at {}, try GET /days or POST /days/{{day}}/parts/{{part}}",
+                request.path
+            ),
+        ),
+    }
+}
+
+fn days(solutions: &Registry) -> Response {
+    let listings: Vec<Listing> = solutions
+        .days()
+        .into_iter()
+        .map(|day| {
+            let solution = &solutions[day];
+
+            Listing {
+                metadata: solution.metadata(),
+                parts: solution.parts(),
+                params: solution.params(),
+            }
+        })
+        .collect();
+
+    Response::json(Status::Ok, &listings)
+}
+
+/// Solves the part for the input in the body, with the day's default parameters.
+///
+/// The part is solved on a thread of its own, so the request can be answered once the timeout is up even when the part
+/// doesn't check its cancellation. It's then left to finish, holding on to `permit` until it does.
+fn solve(
+    solutions: &'static Registry,
+    args: &ServeArgs,
+    day: &str,
+    part: &str,
+    body: &[u8],
+    permit: &Arc<Permit>,
+) -> Response {
+    let Some(day) = day
+        .parse::<u32>()
+        .ok()
+        .filter(|day| solutions.days().contains(day))
+    else {
+        return Response::error(Status::NotFound, format!("There is no day {}", day));
+    };
+
+    let solution = &solutions[day];
+
+    let part = match part.parse::<Part>() {
+        Ok(part) if solution.parts().contains(&part) => part,
+        Ok(_) | Err(_) => {
+            return Response::error(
+                Status::NotFound,
+                format!("Day {} has no part {}", day, part),
+            );
+        },
+    };
+
+    let Ok(input) = std::str::from_utf8(body) else {
+        return Response::error(Status::BadRequest, "The input is not UTF-8");
+    };
+
+    let job = Job {
+        day,
+        part,
+        input_name: "request".into(),
+        input: Arc::new(DayInput::new(Ok(input.into()))),
+        solution,
+        implementation: MAIN_IMPLEMENTATION,
+        timeout: Some(args.timeout),
+        params: Arc::default(),
+        explain: false,
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let permit = Arc::clone(permit);
+
+    std::thread::spawn(move || {
+        // the request may have been answered already, then nobody is waiting for the result
+        drop(sender.send(job.solve()));
+
+        drop(permit);
+    });
+
+    let result = match receiver.recv_timeout(args.timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            return Response::error(
+                Status::GatewayTimeout,
+                format!(
+                    "Day {} took longer than {} to solve",
+                    day,
+                    format_duration(args.timeout)
+                ),
+            );
+        },
+        Err(RecvTimeoutError::Disconnected) => {
+            return Response::error(Status::InternalServerError, "The solver stopped");
+        },
+    };
+
+    let status = match result.outcome {
+        Outcome::Solved(_) => Status::Ok,
+        Outcome::Failed(_) | Outcome::Panicked(_) => Status::UnprocessableContent,
+        Outcome::TimedOut(_) => Status::GatewayTimeout,
+    };
+
+    Response::json(status, &RunReport::from(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    use clap::Parser as _;
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value as JsonValue, json};
+
+    use crate::cli::{Cli, Command, ServeArgs};
+    use crate::registrations;
+    use crate::registry::Registry;
+    use crate::serve::accept;
+
+    /// Starts a server on a free port that lives until the tests end.
+    fn start(flags: &[&str]) -> SocketAddr {
+        let cli = Cli::try_parse_from(["aoc", "serve"].iter().chain(flags)).unwrap();
+
+        let Some(Command::Serve(args)) = cli.command else {
+            panic!("Not a serve command");
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let solutions: &'static Registry =
+            Box::leak(Box::new(Registry::new(registrations()).unwrap()));
+        let args: &'static ServeArgs = Box::leak(Box::new(args));
+
+        std::thread::spawn(move || accept(&listener, solutions, args));
+
+        address
+    }
+
+    /// Sends `request` as is and returns the status code and the body of the response.
+    fn send(address: SocketAddr, request: &str) -> (u16, JsonValue) {
+        let mut stream = TcpStream::connect(address).unwrap();
+
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = String::new();
+
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn post(address: SocketAddr, path: &str, input: &str) -> (u16, JsonValue) {
+        send(
+            address,
+            &format!(
+                "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                path,
+                input.len(),
+                input
+            ),
+        )
+    }
+
+    #[test]
+    fn days_are_listed() {
+        let address = start(&[]);
+
+        let (status, days) = send(address, "GET /days HTTP/1.1\r\nHost: localhost\r\n\r\n");
+
+        assert_eq!(status, 200);
+        assert_eq!(days.as_array().unwrap().len(), 13);
+        assert_eq!(
+            days[8],
+            json!({
+                "year": 2020,
+                "day": 9,
+                "title": "Encoding Error",
+                "parts": [1, 2],
+                "params": [{
+                    "name": "preamble",
+                    "default": "25",
+                    "description": "How many numbers before each number it needs to be the sum of 2 of."
+                }]
+            })
+        );
+        assert_eq!(days[10]["parts"], json!([1, 2, "torus"]));
+    }
+
+    #[test]
+    fn parts_are_solved() {
+        let address = start(&[]);
+
+        let (status, report) = post(
+            address,
+            "/days/1/parts/1",
+            "1721\n979\n366\n299\n675\n1456\n",
+        );
+
+        assert_eq!(status, 200);
+        assert_eq!(report["day"], 1);
+        assert_eq!(report["part"], 1);
+        assert_eq!(report["status"], "solved");
+        assert_eq!(report["answer"], json!({ "type": "I32", "value": 514_579 }));
+        assert!(report["solve_ns"].is_u64());
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let address = start(&["--max-body-size", "64"]);
+
+        let (status, report) = post(address, "/days/1/parts/2", "one\ntwo\n");
+
+        assert_eq!(status, 422);
+        assert_eq!(report["status"], "failed");
+
+        let statuses: Vec<(u16, String)> = [
+            post(address, "/days/14/parts/1", ""),
+            post(address, "/days/1/parts/3", ""),
+            post(address, "/days/1/parts/1", &"1\n".repeat(40)),
+            send(address, "GET /days/1/parts/1 HTTP/1.1\r\n\r\n"),
+            send(address, "GET /answers HTTP/1.1\r\n\r\n"),
+            send(address, "POST /days/1/parts/1 HTTP/1.1\r\n\r\n"),
+            send(address, "hello\r\n\r\n"),
+        ]
+        .into_iter()
+        .map(|(status, body)| (status, body["error"].as_str().unwrap().to_owned()))
+        .collect();
+
+        assert_eq!(
+            statuses,
+            vec![
+                (404, "There is no day 14".to_owned()),
+                (404, "Day 1 has no part 3".to_owned()),
+                (413, "The input can be at most 64 bytes".to_owned()),
+                (405, "GET is not allowed on /days/1/parts/1".to_owned()),
+                (
+                    404,
+                    "Nothing at /answers, try GET /days or POST /days/{day}/parts/{part}"
+                        .to_owned()
+                ),
+                (411, "Send the input with a Content-Length".to_owned()),
+                (400, "Malformed request line".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn slow_requests_time_out() {
+        let address = start(&["--timeout", "100ms"]);
+
+        let mut stream = TcpStream::connect(address).unwrap();
+
+        stream
+            .write_all(b"POST /days/1/parts/1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n1721")
+            .unwrap();
+
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+
+        let mut response = String::new();
+
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"));
+    }
+
+    #[test]
+    fn slow_parts_time_out() {
+        let address = start(&["--timeout", "100ms"]);
+
+        // no 3 of these sum up to 2020, and day 1 only gives up once it tried them all
+        let input = (-2000..2000)
+            .map(|i| (3 * i + 1).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let started = Instant::now();
+
+        let (status, body) = post(address, "/days/1/parts/2", &input);
+
+        assert_eq!(status, 504);
+        assert_eq!(body["error"], "Day 1 took longer than 100.00ms to solve");
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
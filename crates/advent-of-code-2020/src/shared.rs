@@ -1,20 +1,864 @@
-pub trait Day {
-    fn part_1(&self) -> PartSolution;
-    fn part_2(&self) -> PartSolution;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use color_eyre::eyre::{OptionExt as _, bail, eyre};
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+
+use crate::ocr;
+
+pub trait Day: Send + Sync {
+    /// What `parse` turns the puzzle input into, shared by both parts.
+    type Input: Send + Sync + 'static;
+
+    fn metadata(&self) -> Metadata;
+
+    /// The values the parts read through [`Context::param`], which can be overridden with `--param`.
+    fn params(&self) -> &'static [Param] {
+        &[]
+    }
+
+    /// The puzzle input that ships with the binary, used when no other input is given.
+    fn embedded_input(&self) -> &'static str;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report>;
+
+    /// Solvers that loop until something happens should poll [`Context::cancellation`] in their loops.
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report>;
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report>;
+
+    /// Other ways of solving the parts, to check and time against `part_1` and `part_2`, which are the
+    /// [`MAIN_IMPLEMENTATION`].
+    fn implementations(&self) -> &'static [Implementation<Self>]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Extra challenges on top of the puzzle's two parts, run with `--part NAME` or `--bonus`.
+    fn bonus_parts(&self) -> &'static [BonusPart<Self>]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// The day as something that can show its state while solving, if it implements [`Visualize`].
+    fn visualizer(&self) -> Option<&dyn Visualize<Input = Self::Input>> {
+        None
+    }
+}
+
+/// Solutions whose state evolves while they solve, which can show that state as a sequence of frames.
+pub trait Visualize: Day {
+    /// Solves `part` like `part_1` or `part_2` do, handing each step to `frames` along the way.
+    fn visualize(
+        &self,
+        part: u32,
+        input: &Self::Input,
+        context: &Context,
+        frames: &mut dyn FnMut(Frame),
+    ) -> Result<PartSolution, color_eyre::Report>;
+}
+
+/// One step of a [`Visualize`] solution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    /// Rows of cells, one character per cell.
+    Grid(Vec<String>),
+    /// Points on a plane as `(x, y)`, with `y` growing downwards.
+    Points(Vec<(i64, i64)>),
+    Text(String),
+}
+
+/// The name of a part's implementation through `part_1` or `part_2`.
+pub const MAIN_IMPLEMENTATION: &str = "main";
+
+/// Another way of solving a part.
+pub struct Implementation<D>
+where
+    D: Day,
+{
+    pub name: &'static str,
+    pub part: u32,
+    pub solve: fn(&D, &D::Input, &Context) -> Result<PartSolution, color_eyre::Report>,
+}
+
+/// A part that isn't part of the puzzle, solved and verified like the puzzle's own parts.
+pub struct BonusPart<D>
+where
+    D: Day,
+{
+    pub name: &'static str,
+    pub solve: fn(&D, &D::Input, &Context) -> Result<PartSolution, color_eyre::Report>,
+}
+
+/// One of the puzzle's two parts, or a [`BonusPart`] by name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Part {
+    Puzzle(u32),
+    Bonus(String),
+}
+
+impl Part {
+    /// The puzzle's parts, which every day has.
+    pub const PUZZLE: [Part; 2] = [Part::Puzzle(1), Part::Puzzle(2)];
+}
+
+impl From<u32> for Part {
+    fn from(part: u32) -> Self {
+        Part::Puzzle(part)
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<u32>() {
+            Ok(part @ 1..=2) => Ok(Part::Puzzle(part)),
+            Ok(part) => Err(format!(
+                "Part {} does not exist, bonus parts have names",
+                part
+            )),
+            Err(_)
+                if !s.is_empty()
+                    && s.chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+            {
+                Ok(Part::Bonus(s.to_owned()))
+            },
+            Err(_) => Err(format!(
+                "`{}` is not a part, expected 1, 2 or the name of a bonus part",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Part::Puzzle(part) => write!(f, "{}", part),
+            Part::Bonus(ref name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Which puzzle a solution solves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Metadata {
+    pub year: u32,
+    pub day: u32,
+    pub title: &'static str,
+}
+
+/// A value a solution uses that isn't part of the puzzle input, like the number the entries need to sum up to.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+/// Everything a part gets besides its input.
+#[derive(Debug, Default)]
+pub struct Context {
+    cancellation: Cancellation,
+    /// Parameter values that replace their defaults, by name.
+    params: Arc<BTreeMap<String, String>>,
+    explain: Explain,
+}
+
+impl Context {
+    pub fn new(
+        cancellation: Cancellation,
+        params: Arc<BTreeMap<String, String>>,
+        explain: Explain,
+    ) -> Self {
+        Self {
+            cancellation,
+            params,
+            explain,
+        }
+    }
+
+    pub fn cancellation(&self) -> &Cancellation {
+        &self.cancellation
+    }
+
+    /// Where the part writes down how it reached its answer.
+    pub fn explain(&self) -> &Explain {
+        &self.explain
+    }
+
+    /// The notes the part wrote while it was solved with this context.
+    pub fn into_notes(self) -> Vec<Note> {
+        self.explain.into_notes()
+    }
+
+    /// The value of `param`, its default unless it was overridden.
+    pub fn param<T>(&self, param: &Param) -> Result<T, color_eyre::Report>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        let value = self
+            .params
+            .get(param.name)
+            .map_or(param.default, String::as_str);
+
+        value.parse().map_err(|error| {
+            eyre!(
+                "`{}` is not a valid value for parameter `{}`: {}",
+                value,
+                param.name,
+                error
+            )
+        })
+    }
+}
+
+/// Collects the notes a part writes on how it reached its answer, when explaining was asked for with `--explain`.
+///
+/// Otherwise nothing is collected, and the notes aren't even built.
+#[derive(Debug, Default)]
+pub struct Explain {
+    notes: Option<Mutex<Vec<Note>>>,
+}
+
+impl Explain {
+    /// An explain channel that keeps the notes, the default one drops them.
+    pub fn enabled() -> Self {
+        Self {
+            notes: Some(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Records the note `note` builds, `note` is only called when explaining.
+    pub fn note<F>(&self, note: F)
+    where
+        F: FnOnce() -> Note,
+    {
+        if let Some(ref notes) = self.notes {
+            notes
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(note());
+        }
+    }
+
+    pub fn into_notes(self) -> Vec<Note> {
+        self.notes.map_or_else(Vec::new, |notes| {
+            notes.into_inner().unwrap_or_else(PoisonError::into_inner)
+        })
+    }
+}
+
+/// One step a part took towards its answer, with the values that mattered in it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Note {
+    pub step: String,
+    pub values: Vec<(&'static str, String)>,
+}
+
+impl Note {
+    pub fn new<S>(step: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            step: step.into(),
+            values: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with<V>(mut self, name: &'static str, value: V) -> Self
+    where
+        V: std::fmt::Display,
+    {
+        self.values.push((name, value.to_string()));
+
+        self
+    }
+}
+
+impl std::fmt::Display for Note {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.step)?;
+
+        for (index, &(name, ref value)) in self.values.iter().enumerate() {
+            write!(
+                f,
+                "{}{} = {}",
+                if index == 0 { ": " } else { ", " },
+                name,
+                value
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Tells a running part to stop, either because it ran out of time or because it was cancelled.
+#[derive(Debug, Default)]
+pub struct Cancellation {
+    deadline: Option<Instant>,
+    cancelled: AtomicBool,
+}
+
+impl Cancellation {
+    /// A cancellation that also triggers once `deadline` has passed.
+    pub fn with_deadline(deadline: Option<Instant>) -> Self {
+        Self {
+            deadline,
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    #[cfg_attr(not(test), expect(unused, reason = "Library code"))]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Returns [`Cancelled`] as an error once the part should stop, meant to be used with `?`.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The error a part returns when it stopped because of its [`Cancellation`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// A parsed input, with its type erased.
+pub type ParsedInput = Box<dyn Any + Send + Sync>;
+
+/// Object safe version of `Day`, so solutions with different inputs can be stored together.
+pub trait DynDay: Send + Sync {
+    fn metadata(&self) -> Metadata;
+
+    fn params(&self) -> &'static [Param];
+
+    fn embedded_input(&self) -> &'static str;
+
+    fn parse(&self, input: &str) -> Result<ParsedInput, color_eyre::Report>;
+
+    /// The puzzle's parts followed by the bonus parts.
+    fn parts(&self) -> Vec<Part>;
+
+    /// The names of the implementations of `part`, starting with the [`MAIN_IMPLEMENTATION`].
+    fn implementations(&self, part: &Part) -> Vec<&'static str>;
+
+    /// Solves `part` with `implementation` for an input that came out of this day's `parse`.
+    fn solve(
+        &self,
+        part: &Part,
+        implementation: &str,
+        input: &(dyn Any + Send + Sync),
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report>;
+
+    fn visualizes(&self, part: &Part) -> bool;
+
+    /// Solves `part` for an input that came out of this day's `parse`, handing its frames to `frames`.
+    fn visualize(
+        &self,
+        part: &Part,
+        input: &(dyn Any + Send + Sync),
+        context: &Context,
+        frames: &mut dyn FnMut(Frame),
+    ) -> Result<PartSolution, color_eyre::Report>;
+}
+
+fn downcast<D>(input: &(dyn Any + Send + Sync)) -> Result<&D::Input, color_eyre::Report>
+where
+    D: Day + 'static,
+{
+    input.downcast_ref::<D::Input>().ok_or_eyre(format!(
+        "Input was not parsed by {}",
+        std::any::type_name::<D>()
+    ))
+}
+
+impl<D> DynDay for D
+where
+    D: Day + 'static,
+{
+    fn metadata(&self) -> Metadata {
+        Day::metadata(self)
+    }
+
+    fn params(&self) -> &'static [Param] {
+        Day::params(self)
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        Day::embedded_input(self)
+    }
+
+    fn parse(&self, input: &str) -> Result<ParsedInput, color_eyre::Report> {
+        Ok(Box::new(Day::parse(self, input)?))
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        Part::PUZZLE
+            .into_iter()
+            .chain(
+                Day::bonus_parts(self)
+                    .iter()
+                    .map(|bonus| Part::Bonus(bonus.name.to_owned())),
+            )
+            .collect()
+    }
+
+    fn implementations(&self, part: &Part) -> Vec<&'static str> {
+        std::iter::once(MAIN_IMPLEMENTATION)
+            .chain(
+                Day::implementations(self)
+                    .iter()
+                    .filter(|implementation| *part == Part::Puzzle(implementation.part))
+                    .map(|implementation| implementation.name),
+            )
+            .collect()
+    }
+
+    fn solve(
+        &self,
+        part: &Part,
+        implementation: &str,
+        input: &(dyn Any + Send + Sync),
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let input = downcast::<D>(input)?;
+
+        if implementation != MAIN_IMPLEMENTATION {
+            let other = Day::implementations(self)
+                .iter()
+                .find(|other| *part == Part::Puzzle(other.part) && other.name == implementation)
+                .ok_or_else(|| eyre!("Part {} has no implementation `{}`", part, implementation))?;
+
+            return (other.solve)(self, input, context);
+        }
+
+        match *part {
+            Part::Puzzle(1) => self.part_1(input, context),
+            Part::Puzzle(2) => self.part_2(input, context),
+            Part::Bonus(ref name) => {
+                let bonus = Day::bonus_parts(self)
+                    .iter()
+                    .find(|bonus| bonus.name == name)
+                    .ok_or_else(|| eyre!("Part {} does not exist", part))?;
+
+                (bonus.solve)(self, input, context)
+            },
+            Part::Puzzle(_) => bail!("Part {} does not exist", part),
+        }
+    }
+
+    fn visualizes(&self, part: &Part) -> bool {
+        matches!(*part, Part::Puzzle(_)) && self.visualizer().is_some()
+    }
+
+    fn visualize(
+        &self,
+        part: &Part,
+        input: &(dyn Any + Send + Sync),
+        context: &Context,
+        frames: &mut dyn FnMut(Frame),
+    ) -> Result<PartSolution, color_eyre::Report> {
+        let input = downcast::<D>(input)?;
+
+        let (&Part::Puzzle(number), Some(visualizer)) = (part, self.visualizer()) else {
+            bail!(
+                "Day {}, part {} has nothing to visualize",
+                Day::metadata(self).day,
+                part
+            );
+        };
+
+        visualizer.visualize(number, input, context, frames)
+    }
+}
+
+/// How a [`FakeDay`] parses its input.
+#[cfg(test)]
+type FakeParse<I> = Box<dyn Fn(&str) -> Result<I, color_eyre::Report> + Send + Sync>;
+
+/// How a [`FakeDay`] solves a part.
+#[cfg(test)]
+type FakePart<I> =
+    Box<dyn Fn(&I, &Context) -> Result<PartSolution, color_eyre::Report> + Send + Sync>;
+
+/// How a [`FakeDay`] shows its input as frames.
+#[cfg(test)]
+type FakeFrames<I> = Box<dyn Fn(&I, &mut dyn FnMut(Frame)) + Send + Sync>;
+
+/// A day made of closures, for testing what runs the days without writing a solution for each case.
+#[cfg(test)]
+pub struct FakeDay<I>
+where
+    I: Send + Sync + 'static,
+{
+    day: u32,
+    embedded_input: &'static str,
+    parse: FakeParse<I>,
+    part_1: FakePart<I>,
+    part_2: FakePart<I>,
+    params: &'static [Param],
+    implementations: &'static [Implementation<Self>],
+    bonus_parts: &'static [BonusPart<Self>],
+    /// Shows the input as frames before either part is solved, the day visualizes nothing without it.
+    frames: Option<FakeFrames<I>>,
+}
+
+#[cfg(test)]
+impl<I> FakeDay<I>
+where
+    I: Send + Sync + 'static,
+{
+    /// A day with an empty embedded input and neither params, other implementations, bonus parts nor frames.
+    pub fn new<P, S1, S2>(day: u32, parse: P, part_1: S1, part_2: S2) -> Self
+    where
+        P: Fn(&str) -> Result<I, color_eyre::Report> + Send + Sync + 'static,
+        S1: Fn(&I, &Context) -> Result<PartSolution, color_eyre::Report> + Send + Sync + 'static,
+        S2: Fn(&I, &Context) -> Result<PartSolution, color_eyre::Report> + Send + Sync + 'static,
+    {
+        FakeDay {
+            day,
+            embedded_input: "",
+            parse: Box::new(parse),
+            part_1: Box::new(part_1),
+            part_2: Box::new(part_2),
+            params: &[],
+            implementations: &[],
+            bonus_parts: &[],
+            frames: None,
+        }
+    }
+
+    pub fn with_embedded_input(self, embedded_input: &'static str) -> Self {
+        FakeDay {
+            embedded_input,
+            ..self
+        }
+    }
+
+    pub fn with_params(self, params: &'static [Param]) -> Self {
+        FakeDay { params, ..self }
+    }
+
+    pub fn with_implementations(self, implementations: &'static [Implementation<Self>]) -> Self {
+        FakeDay {
+            implementations,
+            ..self
+        }
+    }
+
+    pub fn with_bonus_parts(self, bonus_parts: &'static [BonusPart<Self>]) -> Self {
+        FakeDay {
+            bonus_parts,
+            ..self
+        }
+    }
+
+    pub fn with_frames<F>(self, frames: F) -> Self
+    where
+        F: Fn(&I, &mut dyn FnMut(Frame)) + Send + Sync + 'static,
+    {
+        FakeDay {
+            frames: Some(Box::new(frames)),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+impl<I> Day for FakeDay<I>
+where
+    I: Send + Sync + 'static,
+{
+    type Input = I;
+
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            year: 2020,
+            day: self.day,
+            title: "Fake",
+        }
+    }
+
+    fn params(&self) -> &'static [Param] {
+        self.params
+    }
+
+    fn embedded_input(&self) -> &'static str {
+        self.embedded_input
+    }
+
+    fn parse(&self, input: &str) -> Result<Self::Input, color_eyre::Report> {
+        (self.parse)(input)
+    }
+
+    fn part_1(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        (self.part_1)(input, context)
+    }
+
+    fn part_2(
+        &self,
+        input: &Self::Input,
+        context: &Context,
+    ) -> Result<PartSolution, color_eyre::Report> {
+        (self.part_2)(input, context)
+    }
+
+    fn implementations(&self) -> &'static [Implementation<Self>] {
+        self.implementations
+    }
+
+    fn bonus_parts(&self) -> &'static [BonusPart<Self>] {
+        self.bonus_parts
+    }
+
+    fn visualizer(&self) -> Option<&dyn Visualize<Input = Self::Input>> {
+        self.frames.is_some().then_some(self)
+    }
+}
+
+#[cfg(test)]
+impl<I> Visualize for FakeDay<I>
+where
+    I: Send + Sync + 'static,
+{
+    fn visualize(
+        &self,
+        part: u32,
+        input: &Self::Input,
+        context: &Context,
+        frames: &mut dyn FnMut(Frame),
+    ) -> Result<PartSolution, color_eyre::Report> {
+        if let Some(ref show) = self.frames {
+            show(input, frames);
+        }
+
+        match part {
+            1 => self.part_1(input, context),
+            2 => self.part_2(input, context),
+            _ => bail!("Part {} does not exist", part),
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+/// A part's answer.
+///
+/// Numeric answers compare by value, so `U32(5)` equals `USize(5)`.
+///
+/// Serialized as `{ "type": "U32", "value": 5 }`, integers that don't fit in an `i64` are written as strings so
+/// TOML can hold them too.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", content = "value")]
 pub enum PartSolution {
-    I32(i32),
-    U32(u32),
-    U64(u64),
-    ISize(isize),
-    USize(usize),
+    I32(#[serde(with = "integer")] i32),
+    U32(#[serde(with = "integer")] u32),
+    I64(#[serde(with = "integer")] i64),
+    U64(#[serde(with = "integer")] u64),
+    I128(#[serde(with = "integer")] i128),
+    U128(#[serde(with = "integer")] u128),
+    ISize(#[serde(with = "integer")] isize),
+    USize(#[serde(with = "integer")] usize),
+    BigInt(#[serde(with = "integer")] BigInt),
+    String(String),
     Vec(Vec<String>),
-    #[expect(unused, reason = "Library code")]
+    /// Pixels that draw letters, see [`crate::ocr`].
+    Grid(#[serde(with = "art")] Vec<Vec<bool>>),
     None,
 }
 
+impl PartSolution {
+    /// The answer as an integer, regardless of its width, `None` when it isn't a number.
+    pub fn as_integer(&self) -> Option<BigInt> {
+        match *self {
+            PartSolution::I32(v) => Some(v.into()),
+            PartSolution::U32(v) => Some(v.into()),
+            PartSolution::I64(v) => Some(v.into()),
+            PartSolution::U64(v) => Some(v.into()),
+            PartSolution::I128(v) => Some(v.into()),
+            PartSolution::U128(v) => Some(v.into()),
+            PartSolution::ISize(v) => Some(v.into()),
+            PartSolution::USize(v) => Some(v.into()),
+            PartSolution::BigInt(ref v) => Some(v.clone()),
+            PartSolution::String(_)
+            | PartSolution::Vec(_)
+            | PartSolution::Grid(_)
+            | PartSolution::None => None,
+        }
+    }
+
+    /// The answer as it is shown in tables, a grid as the letters it draws.
+    pub fn answer_text(&self) -> String {
+        match *self {
+            PartSolution::Grid(ref pixels) => {
+                ocr::decode(pixels).unwrap_or_else(|| ocr::render(pixels))
+            },
+            PartSolution::I32(_)
+            | PartSolution::U32(_)
+            | PartSolution::I64(_)
+            | PartSolution::U64(_)
+            | PartSolution::I128(_)
+            | PartSolution::U128(_)
+            | PartSolution::ISize(_)
+            | PartSolution::USize(_)
+            | PartSolution::BigInt(_)
+            | PartSolution::String(_)
+            | PartSolution::Vec(_)
+            | PartSolution::None => self.to_string(),
+        }
+    }
+
+    /// Whether this answer is the recorded `expected` one, numbers are compared by value so the width they were
+    /// recorded with doesn't matter, and a grid matches the letters it draws.
+    pub fn matches(&self, expected: &PartSolution) -> bool {
+        match (self, expected) {
+            (&PartSolution::Grid(_), &PartSolution::String(ref letters)) => {
+                self.answer_text() == *letters
+            },
+            _ => self == expected,
+        }
+    }
+}
+
+/// (De)serializes any integer as a number when it fits in an `i64`, as a string otherwise.
+mod integer {
+    use num_bigint::BigInt;
+    use serde::de::Error as _;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Integer {
+        Signed(i64),
+        Unsigned(u64),
+        Text(String),
+    }
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone + Into<BigInt>,
+        S: Serializer,
+    {
+        let value: BigInt = value.clone().into();
+
+        match i64::try_from(&value) {
+            Ok(small) => serializer.serialize_i64(small),
+            Err(_) => serializer.collect_str(&value),
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<BigInt>,
+        D: Deserializer<'de>,
+    {
+        let value = match Integer::deserialize(deserializer)? {
+            Integer::Signed(value) => BigInt::from(value),
+            Integer::Unsigned(value) => BigInt::from(value),
+            Integer::Text(text) => text
+                .parse::<BigInt>()
+                .map_err(|_| D::Error::custom(format!("`{}` is not an integer", text)))?,
+        };
+
+        T::try_from(value.clone()).map_err(|_| {
+            D::Error::custom(format!(
+                "{} does not fit in {}",
+                value,
+                std::any::type_name::<T>()
+            ))
+        })
+    }
+}
+
+/// (De)serializes pixels as the lines of their art.
+mod art {
+    use serde::{Deserialize as _, Deserializer, Serialize as _, Serializer};
+
+    use crate::ocr;
+
+    #[expect(clippy::ptr_arg, reason = "Signature required by `with`")]
+    pub fn serialize<S>(pixels: &Vec<Vec<bool>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let lines: Vec<String> = ocr::render(pixels).lines().map(str::to_owned).collect();
+
+        lines.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Vec<bool>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let lines = Vec::<String>::deserialize(deserializer)?;
+
+        Ok(ocr::parse_art(&lines.join("\n")))
+    }
+}
+
+impl PartialEq for PartSolution {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (&PartSolution::String(ref left), &PartSolution::String(ref right)) => left == right,
+            (&PartSolution::Vec(ref left), &PartSolution::Vec(ref right)) => left == right,
+            (&PartSolution::Grid(ref left), &PartSolution::Grid(ref right)) => left == right,
+            (&PartSolution::None, &PartSolution::None) => true,
+            _ => match (self.as_integer(), other.as_integer()) {
+                (Some(left), Some(right)) => left == right,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for PartSolution {}
+
 impl From<i32> for PartSolution {
     fn from(v: i32) -> Self {
         PartSolution::I32(v)
@@ -27,36 +871,201 @@ impl From<u32> for PartSolution {
     }
 }
 
+impl From<i64> for PartSolution {
+    fn from(v: i64) -> Self {
+        PartSolution::I64(v)
+    }
+}
+
 impl From<u64> for PartSolution {
     fn from(v: u64) -> Self {
         PartSolution::U64(v)
     }
 }
 
+impl From<i128> for PartSolution {
+    fn from(v: i128) -> Self {
+        PartSolution::I128(v)
+    }
+}
+
+impl From<u128> for PartSolution {
+    fn from(v: u128) -> Self {
+        PartSolution::U128(v)
+    }
+}
+
+impl From<isize> for PartSolution {
+    fn from(v: isize) -> Self {
+        PartSolution::ISize(v)
+    }
+}
+
 impl From<usize> for PartSolution {
     fn from(v: usize) -> Self {
         PartSolution::USize(v)
     }
 }
 
+impl From<BigInt> for PartSolution {
+    fn from(v: BigInt) -> Self {
+        PartSolution::BigInt(v)
+    }
+}
+
+impl From<String> for PartSolution {
+    fn from(v: String) -> Self {
+        PartSolution::String(v)
+    }
+}
+
+impl From<&str> for PartSolution {
+    fn from(v: &str) -> Self {
+        PartSolution::String(v.to_owned())
+    }
+}
+
 impl From<Vec<String>> for PartSolution {
     fn from(v: Vec<String>) -> Self {
         PartSolution::Vec(v)
     }
 }
 
+impl From<Vec<Vec<bool>>> for PartSolution {
+    fn from(v: Vec<Vec<bool>>) -> Self {
+        PartSolution::Grid(v)
+    }
+}
+
 impl std::fmt::Display for PartSolution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match *self {
             PartSolution::I32(x) => x.to_string(),
             PartSolution::U32(x) => x.to_string(),
+            PartSolution::I64(x) => x.to_string(),
             PartSolution::U64(x) => x.to_string(),
+            PartSolution::I128(x) => x.to_string(),
+            PartSolution::U128(x) => x.to_string(),
             PartSolution::ISize(x) => x.to_string(),
             PartSolution::USize(x) => x.to_string(),
+            PartSolution::BigInt(ref x) => x.to_string(),
+            PartSolution::String(ref x) => x.clone(),
             PartSolution::Vec(ref x) => format!("\n{}", x.join("\n")),
+            PartSolution::Grid(ref x) => {
+                format!("{}\n{}", ocr::decode(x).unwrap_or_default(), ocr::render(x))
+            },
             PartSolution::None => "None".to_owned(),
         };
 
         write!(f, "{}", string)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use num_bigint::BigInt;
+    use pretty_assertions::{assert_eq, assert_ne};
+
+    use crate::ocr::parse_art;
+    use crate::shared::{Cancellation, Cancelled, Explain, Note, PartSolution};
+
+    #[test]
+    fn cancel() {
+        let cancellation = Cancellation::default();
+
+        assert_eq!(cancellation.check(), Ok(()));
+
+        cancellation.cancel();
+
+        assert_eq!(cancellation.check(), Err(Cancelled));
+    }
+
+    #[test]
+    fn deadline() {
+        let pending = Cancellation::with_deadline(Some(Instant::now() + Duration::from_secs(60)));
+        let passed = Cancellation::with_deadline(Some(Instant::now()));
+
+        assert_eq!(pending.check(), Ok(()));
+        assert_eq!(passed.check(), Err(Cancelled));
+    }
+
+    #[test]
+    fn notes_are_kept_when_explaining() {
+        let explain = Explain::enabled();
+
+        explain.note(|| Note::new("Found the pair").with("entries", "1721 + 299"));
+        explain.note(|| Note::new("Done"));
+
+        let notes: Vec<String> = explain
+            .into_notes()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(notes, vec!["Found the pair: entries = 1721 + 299", "Done"]);
+    }
+
+    #[test]
+    fn notes_are_not_built_otherwise() {
+        let explain = Explain::default();
+
+        explain.note(|| unreachable!("Notes aren't built without --explain"));
+
+        assert_eq!(explain.into_notes(), vec![]);
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(PartSolution::U32(5), PartSolution::USize(5));
+        assert_eq!(PartSolution::from(-5_isize), PartSolution::I128(-5));
+        assert_eq!(
+            PartSolution::U128(u128::from(u64::MAX) + 1),
+            PartSolution::BigInt(BigInt::from(u64::MAX) + 1)
+        );
+
+        assert_ne!(PartSolution::U32(5), PartSolution::U64(6));
+        assert_ne!(PartSolution::I32(-5), PartSolution::U32(5));
+        assert_ne!(PartSolution::U32(5), PartSolution::from("5"));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(PartSolution::I64(-42).to_string(), "-42");
+        assert_eq!(
+            PartSolution::U128(u128::MAX).to_string(),
+            u128::MAX.to_string()
+        );
+        assert_eq!(
+            PartSolution::from("mxmxvkd,sqjhc,fvjkl".to_owned()).to_string(),
+            "mxmxvkd,sqjhc,fvjkl"
+        );
+        assert_eq!(
+            PartSolution::from(vec!["#..#".to_owned(), ".##.".to_owned()]).to_string(),
+            "\n#..#\n.##."
+        );
+    }
+
+    #[test]
+    fn grid_is_verified_by_its_letters() {
+        let grid = PartSolution::Grid(parse_art(
+            "\
+            #..#.###\n\
+            #..#..#.\n\
+            ####..#.\n\
+            #..#..#.\n\
+            #..#..#.\n\
+            #..#.###",
+        ));
+
+        assert_eq!(grid.answer_text(), "HI");
+        assert_eq!(
+            grid.to_string(),
+            "HI\n#..#.###\n#..#..#.\n####..#.\n#..#..#.\n#..#..#.\n#..#.###"
+        );
+        assert!(grid.matches(&grid.clone()));
+        assert!(grid.matches(&PartSolution::from("HI")));
+        assert!(!grid.matches(&PartSolution::from("H")));
+    }
+}
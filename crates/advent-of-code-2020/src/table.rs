@@ -0,0 +1,48 @@
+/// Renders `rows` as left-aligned columns under `header`.
+pub fn render_table<const N: usize>(header: &[&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = header.map(str::len);
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let render_row = |cells: [&str; N]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+
+        format!("{}\n", line.join("  ").trim_end())
+    };
+
+    let mut table = render_row(*header);
+
+    for row in rows {
+        table.push_str(&render_row(row.each_ref().map(String::as_str)));
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::table::render_table;
+
+    #[test]
+    fn aligns_columns() {
+        let table = render_table(
+            &["Day", "Answer"],
+            &[
+                ["1".to_owned(), "1019571".to_owned()],
+                ["13".to_owned(), "119".to_owned()],
+            ],
+        );
+
+        assert_eq!(table, "Day  Answer\n1    1019571\n13   119\n");
+    }
+}
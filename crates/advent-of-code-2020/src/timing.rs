@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timing {
+    /// Time spent turning the input text into the solution's own representation, if the solution does that separately.
+    pub parse: Option<Duration>,
+    pub solve: Duration,
+}
+
+impl std::fmt::Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.parse {
+            Some(parse) => write!(
+                f,
+                "parsed in {}, solved in {}",
+                format_duration(parse),
+                format_duration(self.solve)
+            ),
+            None => write!(f, "solved in {}", format_duration(self.solve)),
+        }
+    }
+}
+
+/// Runs `f`, returning its result and how long it took.
+pub fn time<T, F>(f: F) -> (T, Duration)
+where
+    F: FnOnce() -> T,
+{
+    let start = Instant::now();
+
+    let result = f();
+
+    (result, start.elapsed())
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_secs_f64() * 1_000_000_000.0;
+
+    if nanos < 1_000.0 {
+        format!("{:.0}ns", nanos)
+    } else if nanos < 1_000_000.0 {
+        format!("{:.2}us", nanos / 1_000.0)
+    } else if nanos < 1_000_000_000.0 {
+        format!("{:.2}ms", nanos / 1_000_000.0)
+    } else {
+        format!("{:.2}s", nanos / 1_000_000_000.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let middle = sorted.len() / 2;
+
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        let count = f64::from(u32::try_from(sorted.len()).unwrap_or(u32::MAX));
+
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / count;
+
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        Some(Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::timing::{Stats, Timing, format_duration};
+
+    #[test]
+    fn stats() {
+        let samples = [2, 4, 4, 4, 5, 5, 7, 9].map(Duration::from_millis);
+
+        let stats = Stats::new(&samples).unwrap();
+
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(
+            stats.median,
+            Duration::from_millis(4) + Duration::from_micros(500)
+        );
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_millis(2));
+    }
+
+    #[test]
+    fn stats_without_samples() {
+        assert_eq!(Stats::new(&[]), None);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_micros(1_500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_millis(2_250)), "2.25s");
+    }
+
+    #[test]
+    fn display() {
+        let timing = Timing {
+            parse: Some(Duration::from_micros(12)),
+            solve: Duration::from_millis(3),
+        };
+
+        assert_eq!(timing.to_string(), "parsed in 12.00us, solved in 3.00ms");
+    }
+}
@@ -0,0 +1,105 @@
+use std::process::ExitCode;
+
+use crate::answers::{Answers, Verification};
+use crate::cli::{Format, VerifyArgs};
+use crate::output::Printer;
+use crate::registry::Registry;
+use crate::runner::{self, Outcome};
+use crate::shared::PartSolution;
+use crate::table::render_table;
+use crate::timing::format_duration;
+
+pub fn verify(solutions: &Registry, args: &VerifyArgs) -> Result<ExitCode, color_eyre::Report> {
+    let mut answers = Answers::load(&args.answers)?;
+
+    let mut printer = Printer::new(args.format);
+    let mut rows = Vec::new();
+    let mut failures = 0_usize;
+    let mut recorded = 0_usize;
+
+    runner::run(solutions, &args.selection, |result| {
+        let (answer, verification) = match result.outcome {
+            Outcome::Solved(ref answer) => {
+                let verification =
+                    answers.verify(&result.input_name, result.day, &result.part, answer);
+
+                if args.record && verification == Verification::Missing {
+                    answers.insert(&result.input_name, result.day, &result.part, answer.clone());
+                    recorded += 1;
+                }
+
+                (answer.answer_text(), verification)
+            },
+            Outcome::Failed(ref message) => (format!("failed: {}", message), Verification::Error),
+            Outcome::Panicked(ref message) => {
+                (format!("panicked: {}", message), Verification::Error)
+            },
+            Outcome::TimedOut(timeout) => (
+                format!("timed out after {}", format_duration(timeout)),
+                Verification::Error,
+            ),
+        };
+
+        match verification {
+            Verification::Fail { .. } | Verification::Error => failures += 1,
+            Verification::Pass | Verification::Missing => {},
+        }
+
+        if matches!(args.format, Format::Text) {
+            let expected = match verification {
+                Verification::Fail { ref expected } => {
+                    expected.answer_text().escape_debug().to_string()
+                },
+                Verification::Pass => answer.escape_debug().to_string(),
+                Verification::Error => answers
+                    .get(&result.input_name, result.day, &result.part)
+                    .map(PartSolution::answer_text)
+                    .unwrap_or_default()
+                    .escape_debug()
+                    .to_string(),
+                Verification::Missing => String::new(),
+            };
+
+            rows.push([
+                result.day.to_string(),
+                result.part.to_string(),
+                result.input_name.to_string(),
+                verification.to_string(),
+                answer.escape_debug().to_string(),
+                expected,
+            ]);
+        } else {
+            printer.print(result, Some(&verification));
+        }
+    })?;
+
+    printer.finish();
+
+    if matches!(args.format, Format::Text) {
+        print!(
+            "{}",
+            render_table(
+                &["Day", "Part", "Input", "Status", "Answer", "Expected"],
+                &rows
+            )
+        );
+    }
+
+    if recorded > 0 {
+        answers.save(&args.answers)?;
+
+        eprintln!(
+            "Recorded {} answer(s) in {}",
+            recorded,
+            args.answers.display()
+        );
+    }
+
+    if failures > 0 {
+        eprintln!("{} part(s) did not match their recorded answer", failures);
+
+        return Ok(ExitCode::FAILURE);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,289 @@
+use std::io::{IsTerminal as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use color_eyre::eyre::WrapErr as _;
+use hashbrown::HashSet;
+
+use crate::cli::Selection;
+use crate::registry::Registry;
+use crate::runner::{self, Job, PartResult};
+use crate::shared::Frame;
+
+/// The largest drawing of a set of points, points that are further apart are scaled down to fit.
+const MAX_WIDTH: i64 = 120;
+const MAX_HEIGHT: i64 = 60;
+
+/// Where the frames go.
+pub enum Target {
+    /// Drawn on stderr, each frame replaces the previous one after `delay` when stderr is a terminal.
+    Terminal { delay: Duration },
+    /// Written to the directory, one `day_XX_part_Y_NNNNN.txt` file per frame.
+    Directory(PathBuf),
+}
+
+impl Target {
+    /// The target `--visualize` asks for, `-` is the terminal.
+    pub fn new(visualize: &Path, delay: Duration) -> Self {
+        if visualize == Path::new("-") {
+            Target::Terminal { delay }
+        } else {
+            Target::Directory(visualize.to_path_buf())
+        }
+    }
+
+    fn show(&self, job: &Job<'_>, number: usize, frame: &Frame) -> Result<(), color_eyre::Report> {
+        match *self {
+            Target::Terminal { delay } => {
+                let mut stderr = std::io::stderr().lock();
+
+                let terminal = stderr.is_terminal();
+
+                if terminal {
+                    // clear the screen and start drawing at the top
+                    write!(stderr, "\x1b[2J\x1b[H")?;
+                }
+
+                writeln!(
+                    stderr,
+                    "Day {}, part {}, frame {}\n{}\n",
+                    job.day,
+                    job.part,
+                    number,
+                    draw(frame)
+                )?;
+
+                stderr.flush()?;
+
+                if terminal {
+                    std::thread::sleep(delay);
+                }
+
+                Ok(())
+            },
+            Target::Directory(ref dir) => {
+                let path = dir.join(format!(
+                    "day_{:02}_part_{}_{:05}.txt",
+                    job.day, job.part, number
+                ));
+
+                std::fs::write(&path, format!("{}\n", draw(frame)))
+                    .wrap_err_with(|| format!("Failed to write frame to {}", path.display()))
+            },
+        }
+    }
+}
+
+/// Solves the selected parts of the days that implement [`crate::shared::Visualize`] one after another, showing
+/// their frames on `target`, and hands each result to `on_result`.
+pub fn visualize<F>(
+    solutions: &Registry,
+    selection: &Selection,
+    target: &Target,
+    mut on_result: F,
+) -> Result<(), color_eyre::Report>
+where
+    F: FnMut(PartResult),
+{
+    let jobs = runner::jobs(solutions, selection)?;
+
+    if let Target::Directory(ref dir) = *target {
+        std::fs::create_dir_all(dir)
+            .wrap_err_with(|| format!("Failed to create {}", dir.display()))?;
+    }
+
+    for job in &jobs {
+        if !job.solution.visualizes(&job.part) {
+            eprintln!(
+                "Day {}, part {} has nothing to visualize",
+                job.day, job.part
+            );
+
+            continue;
+        }
+
+        let mut number = 0;
+        let mut failure = None;
+
+        let result = job.visualize(&mut |frame| {
+            number += 1;
+
+            if failure.is_none() {
+                failure = target.show(job, number, &frame).err();
+            }
+        });
+
+        if let Some(failure) = failure {
+            return Err(failure);
+        }
+
+        on_result(result);
+    }
+
+    Ok(())
+}
+
+/// Renders a frame as text, points are drawn as `#` on a field of `.`.
+fn draw(frame: &Frame) -> String {
+    match *frame {
+        Frame::Grid(ref rows) => rows.join("\n"),
+        Frame::Points(ref points) => draw_points(points),
+        Frame::Text(ref text) => text.clone(),
+    }
+}
+
+fn draw_points(points: &[(i64, i64)]) -> String {
+    let Some(&(first_x, first_y)) = points.first() else {
+        return String::new();
+    };
+
+    let (min_x, max_x, min_y, max_y) = points.iter().fold(
+        (first_x, first_x, first_y, first_y),
+        |(min_x, max_x, min_y, max_y), &(x, y)| {
+            (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+        },
+    );
+
+    // how many units of the plane each character covers, in both directions so the drawing keeps its shape
+    let scale = ((max_x - min_x) / MAX_WIDTH)
+        .max((max_y - min_y) / MAX_HEIGHT)
+        .saturating_add(1);
+
+    let lit: HashSet<(i64, i64)> = points
+        .iter()
+        .map(|&(x, y)| ((x - min_x) / scale, (y - min_y) / scale))
+        .collect();
+
+    let rows: Vec<String> = (0..=(max_y - min_y) / scale)
+        .map(|row| {
+            (0..=(max_x - min_x) / scale)
+                .map(|column| {
+                    if lit.contains(&(column, row)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    rows.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use clap::Parser as _;
+    use pretty_assertions::assert_eq;
+
+    use crate::cli::Cli;
+    use crate::registry::{Registration, Registry};
+    use crate::runner::Outcome;
+    use crate::shared::{FakeDay, Frame, PartSolution};
+    use crate::visualize::{Target, draw, visualize};
+
+    #[test]
+    fn frames_are_written_to_the_directory() {
+        let registry = Registry::new(vec![
+            Registration {
+                module: "countdown",
+                solution: Box::new(
+                    FakeDay::new(
+                        2,
+                        |input| Ok(input.parse::<u32>()?),
+                        |_, _| Ok(PartSolution::U32(0)),
+                        |_, _| Ok(PartSolution::None),
+                    )
+                    .with_embedded_input("3")
+                    .with_frames(|&input, frames| {
+                        for left in (1..=input).rev() {
+                            frames(Frame::Text(left.to_string()));
+                        }
+                    }),
+                ),
+            },
+            Registration {
+                module: "still",
+                solution: Box::new(FakeDay::new(
+                    3,
+                    |_| Ok(()),
+                    |&(), _| Ok(PartSolution::None),
+                    |&(), _| Ok(PartSolution::None),
+                )),
+            },
+        ])
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("aoc-2020-frames-{}", std::process::id()));
+
+        let cli = Cli::try_parse_from(["aoc", "--part", "1"]).unwrap();
+
+        let mut results = Vec::new();
+
+        visualize(
+            &registry,
+            cli.selection(),
+            &Target::Directory(dir.clone()),
+            |result| results.push((result.day, result.outcome)),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            results.as_slice(),
+            &[(2, Outcome::Solved(PartSolution::U32(0)))]
+        ));
+
+        let mut frames: Vec<(PathBuf, String)> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let frame = std::fs::read_to_string(&path).unwrap();
+
+                (path.strip_prefix(&dir).unwrap().to_path_buf(), frame)
+            })
+            .collect();
+
+        frames.sort();
+
+        assert_eq!(
+            frames,
+            vec![
+                (PathBuf::from("day_02_part_1_00001.txt"), "3\n".to_owned()),
+                (PathBuf::from("day_02_part_1_00002.txt"), "2\n".to_owned()),
+                (PathBuf::from("day_02_part_1_00003.txt"), "1\n".to_owned()),
+            ]
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn points() {
+        assert_eq!(
+            draw(&Frame::Points(vec![(-1, -1), (1, 0), (0, 1)])),
+            "#..\n..#\n.#."
+        );
+        assert_eq!(draw(&Frame::Points(vec![])), "");
+    }
+
+    #[test]
+    fn far_points_are_scaled_down() {
+        let drawing = draw(&Frame::Points(vec![(0, 0), (1_000, 10), (0, 10)]));
+
+        let rows: Vec<&str> = drawing.lines().collect();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].len(), 112);
+        assert_eq!(drawing.matches('#').count(), 3);
+    }
+
+    #[test]
+    fn grid() {
+        assert_eq!(
+            draw(&Frame::Grid(vec!["#.L".to_owned(), "L.#".to_owned()])),
+            "#.L\nL.#"
+        );
+    }
+}
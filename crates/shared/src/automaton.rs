@@ -0,0 +1,371 @@
+use std::collections::BTreeSet;
+use std::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::grid::{Coordinate, Direction, Grid};
+
+/// A world of cells that all move on to their next state at once, a generation at a time.
+pub trait Automaton {
+    /// What the world looks like at a generation.
+    type Cells;
+
+    fn cells(&self) -> &Self::Cells;
+
+    /// Moves on to the next generation, returns how many cells changed.
+    fn step(&mut self) -> usize;
+
+    /// Steps until a generation doesn't change anything, `observe` is shown every generation that did and can stop
+    /// the run by failing. Returns how many generations changed something.
+    ///
+    /// # Errors
+    ///
+    /// When `observe` fails.
+    fn run_until_stable<O, E>(&mut self, mut observe: O) -> Result<usize, E>
+    where
+        O: FnMut(&Self::Cells) -> Result<(), E>,
+    {
+        let mut generations = 0;
+
+        while self.step() > 0 {
+            generations += 1;
+
+            observe(self.cells())?;
+        }
+
+        Ok(generations)
+    }
+
+    /// Steps until the world looks like it did at an earlier generation, from then on it repeats forever. `observe` is
+    /// shown every generation and can stop the run by failing.
+    ///
+    /// # Errors
+    ///
+    /// When `observe` fails.
+    fn run_until_repeating<O, E>(&mut self, mut observe: O) -> Result<Repetition, E>
+    where
+        Self::Cells: Clone + Eq + Hash,
+        O: FnMut(&Self::Cells) -> Result<(), E>,
+    {
+        // the generation each world was first seen in
+        let mut seen = HashMap::new();
+        let mut generation = 0;
+
+        loop {
+            if let Some(&start) = seen.get(self.cells()) {
+                return Ok(Repetition {
+                    start,
+                    period: generation - start,
+                });
+            }
+
+            seen.insert(self.cells().clone(), generation);
+
+            self.step();
+            generation += 1;
+
+            observe(self.cells())?;
+        }
+    }
+}
+
+/// Where a run starts repeating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Repetition {
+    /// The first generation that comes back, counted from 0 for the world the run started with.
+    pub start: usize,
+    /// How many generations it takes to come back, 1 for a world that is stable.
+    pub period: usize,
+}
+
+/// Which cells of a grid a cell's next state depends on.
+pub enum Neighbourhood<T> {
+    /// The up to 8 cells around it.
+    Adjacent,
+    /// The first cell in each of the 8 directions that isn't seen through, the cells for which the function returns
+    /// `true` are.
+    LineOfSight(fn(&T) -> bool),
+    /// The cells the function picks.
+    Custom(fn(&Grid<T>, Coordinate) -> Vec<Coordinate>),
+}
+
+impl<T> Neighbourhood<T> {
+    fn collect<'g>(&self, grid: &'g Grid<T>, coordinate: Coordinate, neighbours: &mut Vec<&'g T>) {
+        match *self {
+            Neighbourhood::Adjacent => {
+                neighbours.extend(
+                    grid.neighbours_8(coordinate)
+                        .map(|neighbour| &grid[neighbour]),
+                );
+            },
+            Neighbourhood::LineOfSight(see_through) => {
+                neighbours.extend(Direction::ALL.into_iter().filter_map(|direction| {
+                    grid.line(coordinate, direction)
+                        .map(|(_, cell)| cell)
+                        .find(|&cell| !see_through(cell))
+                }));
+            },
+            Neighbourhood::Custom(pick) => {
+                neighbours.extend(
+                    pick(grid, coordinate)
+                        .into_iter()
+                        .map(|neighbour| &grid[neighbour]),
+                );
+            },
+        }
+    }
+}
+
+/// An automaton on a grid, `rule` gets each cell with its neighbours and returns what the cell becomes.
+pub struct GridAutomaton<T, F> {
+    now: Grid<T>,
+    next: Grid<T>,
+    neighbourhood: Neighbourhood<T>,
+    rule: F,
+}
+
+impl<T, F> GridAutomaton<T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &[&T]) -> T,
+{
+    #[must_use]
+    pub fn new(cells: Grid<T>, neighbourhood: Neighbourhood<T>, rule: F) -> Self {
+        GridAutomaton {
+            next: cells.clone(),
+            now: cells,
+            neighbourhood,
+            rule,
+        }
+    }
+}
+
+impl<T, F> Automaton for GridAutomaton<T, F>
+where
+    T: Clone + PartialEq,
+    F: FnMut(&T, &[&T]) -> T,
+{
+    type Cells = Grid<T>;
+
+    fn cells(&self) -> &Grid<T> {
+        &self.now
+    }
+
+    fn step(&mut self) -> usize {
+        let mut changed = 0;
+        let mut neighbours = Vec::new();
+
+        for (coordinate, cell) in self.now.iter() {
+            neighbours.clear();
+
+            self.neighbourhood
+                .collect(&self.now, coordinate, &mut neighbours);
+
+            let next = (self.rule)(cell, &neighbours);
+
+            if next != *cell {
+                changed += 1;
+            }
+
+            self.next[coordinate] = next;
+        }
+
+        // the next generation is written over the one before, there's no need to allocate a grid every step
+        std::mem::swap(&mut self.now, &mut self.next);
+
+        changed
+    }
+}
+
+/// An automaton on an endless grid in `N` dimensions, where cells are either live or dead and only the live ones are
+/// stored. Every cell has the `3^N - 1` cells around it as neighbours, and `rule` gets whether a cell is live with how
+/// many of its neighbours are, and returns whether it will be live. Dead cells without live neighbours stay dead.
+pub struct SparseWorld<const N: usize, F> {
+    live: BTreeSet<[i64; N]>,
+    offsets: Vec<[i64; N]>,
+    rule: F,
+}
+
+impl<const N: usize, F> SparseWorld<N, F>
+where
+    F: FnMut(bool, usize) -> bool,
+{
+    #[must_use]
+    pub fn new<I>(live: I, rule: F) -> Self
+    where
+        I: IntoIterator<Item = [i64; N]>,
+    {
+        let mut offsets = vec![[0; N]];
+
+        for axis in 0..N {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|offset| {
+                    [-1, 0, 1].map(|delta| {
+                        let mut neighbour = offset;
+
+                        neighbour[axis] = delta;
+
+                        neighbour
+                    })
+                })
+                .collect();
+        }
+
+        offsets.retain(|offset| offset.iter().any(|&delta| delta != 0));
+
+        SparseWorld {
+            live: live.into_iter().collect(),
+            offsets,
+            rule,
+        }
+    }
+}
+
+impl<const N: usize, F> Automaton for SparseWorld<N, F>
+where
+    F: FnMut(bool, usize) -> bool,
+{
+    type Cells = BTreeSet<[i64; N]>;
+
+    fn cells(&self) -> &BTreeSet<[i64; N]> {
+        &self.live
+    }
+
+    fn step(&mut self) -> usize {
+        let mut live_neighbours: HashMap<[i64; N], usize> = HashMap::new();
+
+        for cell in &self.live {
+            for offset in &self.offsets {
+                *live_neighbours
+                    .entry(std::array::from_fn(|axis| cell[axis] + offset[axis]))
+                    .or_default() += 1;
+            }
+        }
+
+        let mut next = BTreeSet::new();
+
+        for &cell in &self.live {
+            if !live_neighbours.contains_key(&cell) && (self.rule)(true, 0) {
+                next.insert(cell);
+            }
+        }
+
+        for (cell, count) in live_neighbours {
+            if (self.rule)(self.live.contains(&cell), count) {
+                next.insert(cell);
+            }
+        }
+
+        let changed = self.live.symmetric_difference(&next).count();
+
+        self.live = next;
+
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::automaton::{Automaton as _, GridAutomaton, Neighbourhood, Repetition, SparseWorld};
+    use crate::grid::{Coordinate, Grid};
+
+    fn life(live: bool, neighbours: usize) -> bool {
+        neighbours == 3 || (live && neighbours == 2)
+    }
+
+    fn cells(text: &str) -> Grid<bool> {
+        Grid::parse(text, |c| Some(c == '#')).unwrap()
+    }
+
+    #[test]
+    fn grid() {
+        let grid_life = |&live: &bool, neighbours: &[&bool]| {
+            life(
+                live,
+                neighbours.iter().filter(|&&&neighbour| neighbour).count(),
+            )
+        };
+
+        let mut blinker =
+            GridAutomaton::new(cells("...\n###\n..."), Neighbourhood::Adjacent, grid_life);
+
+        assert_eq!(blinker.step(), 4);
+        assert_eq!(blinker.cells(), &cells(".#.\n.#.\n.#."));
+        assert_eq!(
+            blinker.run_until_repeating(|_| Ok::<_, Infallible>(())),
+            Ok(Repetition {
+                start: 0,
+                period: 2
+            })
+        );
+
+        let mut block = GridAutomaton::new(
+            cells("##...\n##...\n....#"),
+            Neighbourhood::Adjacent,
+            grid_life,
+        );
+
+        assert_eq!(block.run_until_stable(|_| Ok::<_, Infallible>(())), Ok(1));
+        assert_eq!(block.cells(), &cells("##...\n##...\n....."));
+    }
+
+    #[test]
+    fn neighbourhoods() {
+        fn wrapping(grid: &Grid<u32>, coordinate: Coordinate) -> Vec<Coordinate> {
+            [(-1, 0), (0, 1), (1, 0), (0, -1)]
+                .map(|offset| grid.step_wrapping(coordinate, offset))
+                .to_vec()
+        }
+
+        // every cell becomes the sum of its neighbours
+        let sums = |neighbourhood| {
+            let mut automaton = GridAutomaton::new(
+                Grid::parse("102\n000\n304", |c| c.to_digit(10)).unwrap(),
+                neighbourhood,
+                |_, neighbours: &[&u32]| neighbours.iter().copied().sum(),
+            );
+
+            automaton.step();
+
+            automaton.cells().cells().copied().collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sums(Neighbourhood::Adjacent),
+            vec![0, 3, 0, 4, 10, 6, 0, 7, 0]
+        );
+        assert_eq!(
+            sums(Neighbourhood::LineOfSight(|&cell| cell == 0)),
+            vec![9, 3, 8, 4, 10, 6, 7, 7, 6]
+        );
+        assert_eq!(
+            sums(Neighbourhood::Custom(wrapping)),
+            vec![5, 3, 5, 4, 0, 6, 5, 7, 5]
+        );
+    }
+
+    #[test]
+    fn dimensions() {
+        let live: Vec<(i64, i64)> = cells(".#.\n..#\n###")
+            .iter()
+            .filter(|&(_, &cell)| cell)
+            .map(|((row, column), _)| (i64::try_from(row).unwrap(), i64::try_from(column).unwrap()))
+            .collect();
+
+        let mut cubes = SparseWorld::new(live.iter().map(|&(x, y)| [x, y, 0]), life);
+        let mut hypercubes = SparseWorld::new(live.iter().map(|&(x, y)| [x, y, 0, 0]), life);
+
+        for _ in 0..6 {
+            cubes.step();
+            hypercubes.step();
+        }
+
+        assert_eq!(cubes.cells().len(), 112);
+        assert_eq!(hypercubes.cells().len(), 848);
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+
+use hashbrown::HashMap;
+
+/// A node of a [`Graph`], its place in the graph's arena.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Which way edges are followed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// From a node to the nodes it has edges to.
+    Outgoing,
+    /// From a node back to the nodes that have edges to it.
+    Incoming,
+}
+
+/// Nodes that lead back to themselves, each one has an edge to the next and the last one to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    nodes: Vec<NodeId>,
+}
+
+impl Cycle {
+    #[must_use]
+    pub fn nodes(&self) -> &[NodeId] {
+        &self.nodes
+    }
+}
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The graph has a cycle of {} node(s)", self.nodes.len())
+    }
+}
+
+impl std::error::Error for Cycle {}
+
+/// A directed graph with weighted edges. The nodes live in an arena and are named, each name is stored once.
+#[derive(Clone, Debug)]
+pub struct Graph<W> {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    outgoing: Vec<Vec<(NodeId, W)>>,
+    incoming: Vec<Vec<(NodeId, W)>>,
+}
+
+impl<W> Default for Graph<W> {
+    fn default() -> Self {
+        Graph {
+            names: Vec::new(),
+            ids: HashMap::new(),
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+        }
+    }
+}
+
+impl<W> Graph<W> {
+    #[must_use]
+    pub fn new() -> Self {
+        Graph::default()
+    }
+
+    /// The node called `name`, it's added when there isn't one yet.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&node) = self.ids.get(name) {
+            return node;
+        }
+
+        let node = NodeId(self.names.len());
+
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), node);
+        self.outgoing.push(Vec::new());
+        self.incoming.push(Vec::new());
+
+        node
+    }
+
+    /// The node called `name`, if there is one.
+    #[must_use]
+    pub fn node(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    #[must_use]
+    pub fn name(&self, node: NodeId) -> &str {
+        &self.names[node.0]
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Every node, in the order they were added.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + use<W> {
+        (0..self.names.len()).map(NodeId)
+    }
+
+    /// Adds an edge from `from` to `to`, it can be followed back from `to` as well.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: W)
+    where
+        W: Clone,
+    {
+        self.incoming[to.0].push((from, weight.clone()));
+        self.outgoing[from.0].push((to, weight));
+    }
+
+    /// The edges of `node` that go the `direction` way, with the nodes on their other end.
+    #[must_use]
+    pub fn edges(&self, node: NodeId, direction: Direction) -> &[(NodeId, W)] {
+        match direction {
+            Direction::Outgoing => &self.outgoing[node.0],
+            Direction::Incoming => &self.incoming[node.0],
+        }
+    }
+
+    /// The nodes that can be reached from `start`, depth first. `start` comes first and every node comes before the
+    /// ones that were found through it.
+    #[must_use]
+    pub fn dfs(&self, start: NodeId, direction: Direction) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut seen[node.0], true) {
+                continue;
+            }
+
+            order.push(node);
+
+            // reversed, so the first edge is followed first
+            stack.extend(
+                self.edges(node, direction)
+                    .iter()
+                    .rev()
+                    .map(|&(next, _)| next),
+            );
+        }
+
+        order
+    }
+
+    /// The nodes that can be reached from `start`, breadth first, so by how many edges away they are. `start` comes
+    /// first.
+    #[must_use]
+    pub fn bfs(&self, start: NodeId, direction: Direction) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+
+        seen[start.0] = true;
+
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+
+            for &(next, _) in self.edges(node, direction) {
+                if !std::mem::replace(&mut seen[next.0], true) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        order
+    }
+
+    /// The nodes that can be reached from `starts` along outgoing edges, every node after the nodes it has edges to.
+    fn post_order<I>(&self, starts: I) -> Result<Vec<NodeId>, Cycle>
+    where
+        I: IntoIterator<Item = NodeId>,
+    {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Visit {
+            New,
+            Open,
+            Done,
+        }
+
+        let mut visits = vec![Visit::New; self.len()];
+        let mut order = Vec::new();
+
+        for start in starts {
+            if visits[start.0] != Visit::New {
+                continue;
+            }
+
+            visits[start.0] = Visit::Open;
+
+            // the nodes being visited, with how many of their edges have been followed
+            let mut path = vec![(start, 0)];
+
+            while let Some(top) = path.last_mut() {
+                let node = top.0;
+
+                let Some(&(next, _)) = self.outgoing[node.0].get(top.1) else {
+                    visits[node.0] = Visit::Done;
+                    order.push(node);
+                    path.pop();
+
+                    continue;
+                };
+
+                top.1 += 1;
+
+                match visits[next.0] {
+                    Visit::New => {
+                        visits[next.0] = Visit::Open;
+                        path.push((next, 0));
+                    },
+                    Visit::Open => {
+                        return Err(Cycle {
+                            nodes: path
+                                .iter()
+                                .map(|&(on_path, _)| on_path)
+                                .skip_while(|&on_path| on_path != next)
+                                .collect(),
+                        });
+                    },
+                    Visit::Done => {},
+                }
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Every node, each one before the nodes it has edges to.
+    ///
+    /// # Errors
+    ///
+    /// When the graph has a cycle, as then there is no such order.
+    pub fn topological_sort(&self) -> Result<Vec<NodeId>, Cycle> {
+        let mut order = self.post_order(self.nodes())?;
+
+        order.reverse();
+
+        Ok(order)
+    }
+
+    /// A cycle of the graph, if it has any.
+    #[must_use]
+    pub fn find_cycle(&self) -> Option<Cycle> {
+        self.post_order(self.nodes()).err()
+    }
+
+    /// Works out a value for `start` from the values of the nodes it has edges to, and so on down. `f` gets a node
+    /// with the weight and the value of each of its outgoing edges, and is called once per node however many edges
+    /// lead to it.
+    ///
+    /// # Errors
+    ///
+    /// When a cycle can be reached from `start`, its nodes would depend on themselves.
+    ///
+    /// # Panics
+    ///
+    /// When `start` isn't a node of this graph.
+    pub fn fold<T, F>(&self, start: NodeId, mut f: F) -> Result<T, Cycle>
+    where
+        F: FnMut(NodeId, &[(&W, &T)]) -> T,
+    {
+        let mut values: Vec<Option<T>> = self.nodes().map(|_| None).collect();
+
+        for node in self.post_order([start])? {
+            // in post-order the nodes on the other end of the edges come first, so their values are known
+            let targets: Vec<(&W, &T)> = self.outgoing[node.0]
+                .iter()
+                .filter_map(|&(target, ref weight)| Some((weight, values[target.0].as_ref()?)))
+                .collect();
+
+            let value = f(node, &targets);
+
+            values[node.0] = Some(value);
+        }
+
+        Ok(values[start.0]
+            .take()
+            .expect("The start is the last node in post-order"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::graph::{Direction, Graph, NodeId};
+
+    /// `a -> b -> d`, `a -> c -> d`, `c -> e`, weighted by the index of the target.
+    fn diamond() -> Graph<u32> {
+        let mut graph = Graph::new();
+
+        for (from, to, weight) in [
+            ("a", "b", 1),
+            ("a", "c", 2),
+            ("b", "d", 3),
+            ("c", "d", 3),
+            ("c", "e", 4),
+        ] {
+            let from = graph.intern(from);
+            let to = graph.intern(to);
+
+            graph.add_edge(from, to, weight);
+        }
+
+        graph
+    }
+
+    fn names(graph: &Graph<u32>, nodes: &[NodeId]) -> String {
+        nodes.iter().map(|&node| graph.name(node)).collect()
+    }
+
+    #[test]
+    fn interning() {
+        let mut graph = diamond();
+
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.intern("c"), graph.node("c").unwrap());
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.node("f"), None);
+        assert_eq!(graph.name(graph.node("e").unwrap()), "e");
+    }
+
+    #[test]
+    fn traversals() {
+        let graph = diamond();
+        let a = graph.node("a").unwrap();
+        let d = graph.node("d").unwrap();
+
+        assert_eq!(names(&graph, &graph.dfs(a, Direction::Outgoing)), "abdce");
+        assert_eq!(names(&graph, &graph.bfs(a, Direction::Outgoing)), "abcde");
+        assert_eq!(names(&graph, &graph.bfs(d, Direction::Incoming)), "dbca");
+        assert_eq!(
+            graph
+                .edges(d, Direction::Incoming)
+                .iter()
+                .map(|&(_, weight)| weight)
+                .collect::<Vec<_>>(),
+            vec![3, 3]
+        );
+    }
+
+    #[test]
+    fn order_and_cycles() {
+        let mut graph = diamond();
+
+        assert_eq!(names(&graph, &graph.topological_sort().unwrap()), "acebd");
+        assert_eq!(graph.find_cycle(), None);
+
+        let a = graph.node("a").unwrap();
+
+        // the paths below `a`, counted once per node
+        let paths = graph.fold(a, |_, targets| {
+            targets.iter().map(|&(_, &paths)| paths).sum::<u32>().max(1)
+        });
+
+        assert_eq!(paths, Ok(3));
+
+        let d = graph.node("d").unwrap();
+        let b = graph.node("b").unwrap();
+
+        graph.add_edge(d, b, 0);
+
+        let cycle = graph.find_cycle().unwrap();
+
+        assert_eq!(names(&graph, cycle.nodes()), "bd");
+        assert_eq!(graph.topological_sort(), Err(cycle.clone()));
+        assert_eq!(graph.fold(a, |_, _| 0), Err(cycle));
+    }
+}
@@ -0,0 +1,510 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+/// A cell's position, as `(row, column)`, counted from the top left.
+pub type Coordinate = (usize, usize);
+
+/// The 8 ways to go from a cell to a neighbouring one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// Up, right, down and left.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// How one step in this direction changes the row and the column.
+    #[must_use]
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::UpRight => (-1, 1),
+            Direction::Right => (0, 1),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (1, 0),
+            Direction::DownLeft => (1, -1),
+            Direction::Left => (0, -1),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+}
+
+/// Why text couldn't be read as a grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// A line isn't as long as the first one.
+    Ragged {
+        line: usize,
+        length: usize,
+        expected: usize,
+    },
+    /// A character doesn't stand for any cell.
+    Cell {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParseGridError::Ragged {
+                line,
+                length,
+                expected,
+            } => write!(
+                f,
+                "Line {} is {} long, expected {} like the first line",
+                line, length, expected
+            ),
+            ParseGridError::Cell {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "Line {}, column {}: `{}` is not a cell",
+                line, column, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+/// A rectangle of cells, stored row after row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T> Grid<T> {
+    /// A grid of `rows` by `columns` cells that all start out as `value`.
+    #[must_use]
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; rows * columns],
+            rows,
+            columns,
+        }
+    }
+
+    /// Reads a grid with a line of text per row, `cell` turns each character into a cell.
+    ///
+    /// # Errors
+    ///
+    /// When the lines aren't all as long, or when `cell` doesn't accept a character.
+    pub fn parse<F>(text: &str, mut cell: F) -> Result<Self, ParseGridError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = Vec::with_capacity(text.len());
+        let mut rows = 0;
+        let mut columns = None;
+
+        for (line, text) in (1..).zip(text.lines()) {
+            let before = cells.len();
+
+            for (column, found) in (1..).zip(text.chars()) {
+                cells.push(cell(found).ok_or(ParseGridError::Cell {
+                    line,
+                    column,
+                    found,
+                })?);
+            }
+
+            let length = cells.len() - before;
+            let expected = *columns.get_or_insert(length);
+
+            if length != expected {
+                return Err(ParseGridError::Ragged {
+                    line,
+                    length,
+                    expected,
+                });
+            }
+
+            rows += 1;
+        }
+
+        Ok(Grid {
+            cells,
+            rows,
+            columns: columns.unwrap_or_default(),
+        })
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The cell at `coordinate`, if it is inside the grid.
+    #[must_use]
+    pub fn get(&self, coordinate: Coordinate) -> Option<&T> {
+        self.position(coordinate)
+            .map(|position| &self.cells[position])
+    }
+
+    pub fn get_mut(&mut self, coordinate: Coordinate) -> Option<&mut T> {
+        self.position(coordinate)
+            .map(|position| &mut self.cells[position])
+    }
+
+    fn position(&self, (row, column): Coordinate) -> Option<usize> {
+        (row < self.rows && column < self.columns).then_some(row * self.columns + column)
+    }
+
+    /// The coordinate `rows` down and `columns` right of `coordinate`, if that is still inside the grid.
+    #[must_use]
+    pub fn step(
+        &self,
+        (row, column): Coordinate,
+        (rows, columns): (isize, isize),
+    ) -> Option<Coordinate> {
+        let row = row.checked_add_signed(rows)?;
+        let column = column.checked_add_signed(columns)?;
+
+        (row < self.rows && column < self.columns).then_some((row, column))
+    }
+
+    /// The coordinate `rows` down and `columns` right of `coordinate`, where leaving the grid on one side enters it
+    /// again on the opposite side.
+    ///
+    /// # Panics
+    ///
+    /// When the grid is empty.
+    #[must_use]
+    pub fn step_wrapping(
+        &self,
+        (row, column): Coordinate,
+        (rows, columns): (isize, isize),
+    ) -> Coordinate {
+        (
+            wrap(row, rows, self.rows),
+            wrap(column, columns, self.columns),
+        )
+    }
+
+    /// The cells up, right, down and left of `coordinate` that are inside the grid.
+    pub fn neighbours_4(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |direction| self.step(coordinate, direction.offset()))
+    }
+
+    /// The cells around `coordinate`, diagonals included, that are inside the grid.
+    pub fn neighbours_8(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(coordinate, direction.offset()))
+    }
+
+    /// The cells from `coordinate` in `direction` up to the edge, `coordinate` itself excluded. Going right is a row,
+    /// going down a column, and going diagonally a diagonal.
+    pub fn line(
+        &self,
+        coordinate: Coordinate,
+        direction: Direction,
+    ) -> impl Iterator<Item = (Coordinate, &T)> + '_ {
+        std::iter::successors(self.step(coordinate, direction.offset()), move |&next| {
+            self.step(next, direction.offset())
+        })
+        .map(|coordinate| (coordinate, &self[coordinate]))
+    }
+
+    /// The cells of a row, left to right.
+    ///
+    /// # Panics
+    ///
+    /// When the row is outside of the grid.
+    #[must_use]
+    pub fn row(&self, row: usize) -> &[T] {
+        assert!(row < self.rows, "Row {} is outside of the grid", row);
+
+        &self.cells[row * self.columns..(row + 1) * self.columns]
+    }
+
+    /// The cells of a column, top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// When the column is outside of the grid.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(
+            column < self.columns,
+            "Column {} is outside of the grid",
+            column
+        );
+
+        self.cells.iter().skip(column).step_by(self.columns)
+    }
+
+    /// Every cell with its coordinate, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &T)> + '_ {
+        (0..self.rows)
+            .flat_map(move |row| (0..self.columns).map(move |column| (row, column)))
+            .zip(&self.cells)
+    }
+
+    /// Every cell, row after row.
+    pub fn cells(&self) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter()
+    }
+
+    #[must_use]
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            columns: self.columns,
+        }
+    }
+
+    /// Builds a grid of `rows` by `columns` by asking `cell` for the cell at each coordinate.
+    fn from_fn<F>(rows: usize, columns: usize, cell: F) -> Self
+    where
+        F: FnMut(Coordinate) -> T,
+    {
+        Grid {
+            cells: (0..rows)
+                .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                .map(cell)
+                .collect(),
+            rows,
+            columns,
+        }
+    }
+
+    /// The grid turned a quarter clockwise, the first column becomes the first row.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.columns, self.rows, |(row, column)| {
+            self[(self.rows - 1 - column, row)].clone()
+        })
+    }
+
+    /// The grid turned a quarter counterclockwise, the last column becomes the first row.
+    #[must_use]
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.columns, self.rows, |(row, column)| {
+            self[(column, self.columns - 1 - row)].clone()
+        })
+    }
+
+    /// The grid mirrored left to right.
+    #[must_use]
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.rows, self.columns, |(row, column)| {
+            self[(row, self.columns - 1 - column)].clone()
+        })
+    }
+
+    /// The grid mirrored top to bottom.
+    #[must_use]
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.rows, self.columns, |(row, column)| {
+            self[(self.rows - 1 - row, column)].clone()
+        })
+    }
+}
+
+/// Moves `index` by `offset` on a ring of `length` indices.
+fn wrap(index: usize, offset: isize, length: usize) -> usize {
+    assert!(length > 0, "Nothing to wrap around in an empty grid");
+
+    // stepping `offset` is the same as stepping its remainder forward, which stays positive
+    let offset = offset.rem_euclid(isize::try_from(length).expect("Grids fit in memory"));
+
+    (index % length + offset.unsigned_abs()) % length
+}
+
+impl<T> Index<Coordinate> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Coordinate) -> &Self::Output {
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside of the {}x{} grid",
+                index, self.rows, self.columns
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<Coordinate> for Grid<T> {
+    fn index_mut(&mut self, index: Coordinate) -> &mut Self::Output {
+        let (rows, columns) = (self.rows, self.columns);
+
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", index, rows, columns))
+    }
+}
+
+/// Draws the grid with a line per row.
+impl<T> Display for Grid<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            if row > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in self.row(row) {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::grid::{Direction, Grid, ParseGridError};
+
+    fn digits(text: &str) -> Grid<u32> {
+        Grid::parse(text, |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let grid = digits("123\n456\n");
+
+        assert_eq!((grid.rows(), grid.columns()), (2, 3));
+        assert_eq!(grid.get((1, 2)), Some(&6));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        assert_eq!(
+            Grid::parse("12\n345", |c| c.to_digit(10)),
+            Err(ParseGridError::Ragged {
+                line: 2,
+                length: 3,
+                expected: 2
+            })
+        );
+        assert_eq!(
+            Grid::parse("12\n3x", |c| c.to_digit(10)),
+            Err(ParseGridError::Cell {
+                line: 2,
+                column: 2,
+                found: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = digits("123\n456\n789");
+
+        let around = |coordinate| {
+            grid.neighbours_8(coordinate)
+                .map(|coordinate| grid[coordinate])
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(around((1, 1)), vec![2, 3, 6, 9, 8, 7, 4, 1]);
+        assert_eq!(around((0, 0)), vec![2, 5, 4]);
+        assert_eq!(
+            grid.neighbours_4((2, 2))
+                .map(|coordinate| grid[coordinate])
+                .collect::<Vec<_>>(),
+            vec![6, 8]
+        );
+    }
+
+    #[test]
+    fn wrapping() {
+        let grid = digits("123\n456\n789");
+
+        assert_eq!(grid.step((0, 0), (-1, 0)), None);
+        assert_eq!(grid.step_wrapping((0, 0), (-1, -1)), (2, 2));
+        assert_eq!(grid.step_wrapping((2, 1), (1, 7)), (0, 2));
+    }
+
+    #[test]
+    fn lines() {
+        let grid = digits("123\n456\n789");
+
+        let line = |coordinate, direction| {
+            grid.line(coordinate, direction)
+                .map(|(_, &cell)| cell)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(line((0, 0), Direction::DownRight), vec![5, 9]);
+        assert_eq!(line((2, 0), Direction::UpRight), vec![5, 3]);
+        assert_eq!(line((1, 2), Direction::Left), vec![5, 4]);
+        assert_eq!(line((0, 1), Direction::Up), Vec::<u32>::new());
+
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6, 9]);
+    }
+
+    #[test]
+    fn rotate_and_flip() {
+        let grid = digits("123\n456");
+
+        assert_eq!(grid.rotate_clockwise().to_string(), "41\n52\n63");
+        assert_eq!(grid.rotate_counterclockwise().to_string(), "36\n25\n14");
+        assert_eq!(grid.flip_horizontal().to_string(), "321\n654");
+        assert_eq!(grid.flip_vertical().to_string(), "456\n123");
+        assert_eq!(grid.rotate_clockwise().rotate_counterclockwise(), grid);
+    }
+}
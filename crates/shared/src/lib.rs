@@ -1 +1,5 @@
-
+pub mod automaton;
+pub mod graph;
+pub mod grid;
+pub mod math;
+pub mod parser;
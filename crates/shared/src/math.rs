@@ -0,0 +1,263 @@
+use std::fmt::{self, Display};
+
+/// The greatest common divisor, `gcd(0, 0)` being 0.
+#[must_use]
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// The least common multiple, or `None` when it doesn't fit in a `u64`.
+#[must_use]
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The extended Euclidean algorithm, returns `(g, x, y)` with `a * x + b * y = g`, where `g` is the greatest common
+/// divisor of `a` and `b` when they aren't negative.
+#[must_use]
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_remainder, mut remainder) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while remainder != 0 {
+        let quotient = old_remainder / remainder;
+
+        (old_remainder, remainder) = (remainder, old_remainder - quotient * remainder);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    (old_remainder, old_x, old_y)
+}
+
+/// The `x` with `a * x ≡ 1 (mod modulus)`, when `a` and `modulus` are coprime.
+#[must_use]
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+
+    let (g, x, _) = ext_gcd(i128::from(a), i128::from(modulus));
+
+    if g == 1 {
+        u64::try_from(x.rem_euclid(i128::from(modulus))).ok()
+    } else {
+        None
+    }
+}
+
+/// `a * b % modulus`, multiplied in 128 bits so it can't overflow.
+///
+/// # Panics
+///
+/// When `modulus` is 0.
+#[must_use]
+pub fn mulmod(a: u64, b: u64, modulus: u64) -> u64 {
+    let product = u128::from(a) * u128::from(b) % u128::from(modulus);
+
+    u64::try_from(product).expect("A remainder is smaller than the modulus")
+}
+
+/// `a * b % modulus`, or `None` when `a * b` overflows or `modulus` is 0.
+#[must_use]
+pub fn checked_mulmod(a: u64, b: u64, modulus: u64) -> Option<u64> {
+    a.checked_mul(b)?.checked_rem(modulus)
+}
+
+/// `base` to the power of `exponent`, modulo `modulus`.
+///
+/// # Panics
+///
+/// When `modulus` is 0.
+#[must_use]
+pub fn modpow(mut base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+
+    base %= modulus;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mulmod(result, base, modulus);
+        }
+
+        base = mulmod(base, base, modulus);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// Why congruences couldn't be combined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrtError {
+    /// No number satisfies all of them.
+    NoSolution,
+    /// The combined modulus doesn't fit in a `u64`.
+    Overflow,
+}
+
+impl Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            CrtError::NoSolution => write!(f, "The congruences have no common solution"),
+            CrtError::Overflow => write!(f, "The combined modulus doesn't fit in 64 bits"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+/// `x ≡ residue (mod modulus)`, the numbers `x` that leave `residue` when divided by `modulus`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Congruence {
+    residue: u64,
+    modulus: u64,
+}
+
+impl Congruence {
+    /// Every number.
+    pub const ANY: Congruence = Congruence {
+        residue: 0,
+        modulus: 1,
+    };
+
+    /// `None` when `modulus` is 0, `residue` is reduced modulo `modulus`.
+    #[must_use]
+    pub fn new(residue: u64, modulus: u64) -> Option<Self> {
+        Some(Congruence {
+            residue: residue.checked_rem(modulus)?,
+            modulus,
+        })
+    }
+
+    /// The smallest number that satisfies the congruence.
+    #[must_use]
+    pub fn residue(&self) -> u64 {
+        self.residue
+    }
+
+    #[must_use]
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// The numbers that satisfy both `self` and `other`, the moduli don't have to be coprime.
+    ///
+    /// # Errors
+    ///
+    /// When no number satisfies both, or when the least common multiple of the moduli doesn't fit in a `u64`.
+    pub fn combine(self, other: Congruence) -> Result<Congruence, CrtError> {
+        let g = gcd(self.modulus, other.modulus);
+
+        // x = self.residue + self.modulus * k, so self.modulus * k ≡ difference (mod other.modulus)
+        let start = self.residue % other.modulus;
+        let difference = if other.residue >= start {
+            other.residue - start
+        } else {
+            other.modulus - (start - other.residue)
+        };
+
+        if difference % g != 0 {
+            return Err(CrtError::NoSolution);
+        }
+
+        let reduced_modulus = other.modulus / g;
+        let modulus = self
+            .modulus
+            .checked_mul(reduced_modulus)
+            .ok_or(CrtError::Overflow)?;
+
+        let inverse = mod_inverse(self.modulus / g % reduced_modulus, reduced_modulus)
+            .ok_or(CrtError::NoSolution)?;
+        let k = mulmod(difference / g, inverse, reduced_modulus);
+
+        // k < reduced_modulus, so this stays below `modulus`
+        Ok(Congruence {
+            residue: self.residue + self.modulus * k,
+            modulus,
+        })
+    }
+}
+
+/// The generalized Chinese remainder theorem, the numbers that satisfy all the `congruences`.
+///
+/// # Errors
+///
+/// When no number satisfies them all, or when the combined modulus doesn't fit in a `u64`.
+pub fn crt<I>(congruences: I) -> Result<Congruence, CrtError>
+where
+    I: IntoIterator<Item = Congruence>,
+{
+    congruences
+        .into_iter()
+        .try_fold(Congruence::ANY, Congruence::combine)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::math::{
+        Congruence, CrtError, checked_mulmod, crt, ext_gcd, gcd, lcm, mod_inverse, modpow, mulmod,
+    };
+
+    fn congruence(residue: u64, modulus: u64) -> Congruence {
+        Congruence::new(residue, modulus).unwrap()
+    }
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u64::MAX, 2), None);
+        assert_eq!(ext_gcd(240, 46), (2, -9, 47));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 6), None);
+        assert_eq!(mod_inverse(5, 1), Some(0));
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mulmod(u64::MAX, u64::MAX, 1_000_000_007), 114_944_269);
+        assert_eq!(checked_mulmod(6, 7, 5), Some(2));
+        assert_eq!(checked_mulmod(u64::MAX, 2, 5), None);
+        assert_eq!(checked_mulmod(6, 7, 0), None);
+        assert_eq!(modpow(2, 10, 1000), 24);
+        assert_eq!(modpow(7, 0, 1), 0);
+        assert_eq!(modpow(3, 1_000_000_006, 1_000_000_007), 1);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(
+            crt([congruence(2, 3), congruence(3, 5), congruence(2, 7)]),
+            Ok(congruence(23, 105))
+        );
+
+        // the moduli share a factor
+        assert_eq!(
+            crt([congruence(0, 4), congruence(4, 6)]),
+            Ok(congruence(4, 12))
+        );
+        assert_eq!(
+            crt([congruence(0, 2), congruence(3, 4)]),
+            Err(CrtError::NoSolution)
+        );
+        assert_eq!(
+            crt([congruence(0, u64::MAX), congruence(0, u64::MAX - 1)]),
+            Err(CrtError::Overflow)
+        );
+        assert_eq!(crt([]), Ok(Congruence::ANY));
+        assert_eq!(Congruence::new(1, 0), None);
+    }
+}
@@ -0,0 +1,575 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// How much of the text after a failure is shown in its message.
+const SNIPPET_LENGTH: usize = 20;
+
+/// Why text couldn't be parsed, with where it went wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    column: usize,
+    expected: String,
+    found: String,
+    position: usize,
+}
+
+impl ParseError {
+    /// The line the failure is on, counted from 1.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column the failure is at, in characters and counted from 1.
+    #[must_use]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The text that was found instead of what was expected, up to the end of its line.
+    #[must_use]
+    pub fn snippet(&self) -> &str {
+        &self.found
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+
+        if self.found.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found `{}`", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// The text being parsed and how far into it parsing has come.
+#[derive(Debug)]
+pub struct Cursor<'a> {
+    text: &'a str,
+    position: usize,
+    /// The failure that came furthest among the ones that were backtracked from, it usually explains a later failure
+    /// better than that failure itself.
+    furthest: Option<ParseError>,
+}
+
+impl<'a> Cursor<'a> {
+    /// The text that is still to be parsed.
+    #[must_use]
+    pub fn rest(&self) -> &'a str {
+        self.text.get(self.position..).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn is_at_end(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    /// A failure right at the cursor, `expected` describes what should have been there.
+    #[must_use]
+    pub fn error<S>(&self, expected: S) -> ParseError
+    where
+        S: Into<String>,
+    {
+        self.error_at(self.position, expected.into())
+    }
+
+    fn error_at(&self, position: usize, expected: String) -> ParseError {
+        let before = self.text.get(..position).unwrap_or_default();
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+
+        let found = self
+            .text
+            .get(position..)
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default();
+
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: before.get(line_start..).unwrap_or_default().chars().count() + 1,
+            expected,
+            found: found.chars().take(SNIPPET_LENGTH).collect(),
+            position,
+        }
+    }
+
+    /// Moves past the first `length` bytes of the rest and returns them.
+    fn advance(&mut self, length: usize) -> &'a str {
+        let rest = self.rest();
+        let (taken, _) = rest.split_at_checked(length).unwrap_or((rest, ""));
+
+        self.position += taken.len();
+
+        taken
+    }
+
+    /// Moves past the characters at the start of the rest that match `predicate` and returns them.
+    fn advance_while<F>(&mut self, predicate: F) -> &'a str
+    where
+        F: Fn(char) -> bool,
+    {
+        let rest = self.rest();
+
+        self.advance(rest.find(|c| !predicate(c)).unwrap_or(rest.len()))
+    }
+
+    /// Goes back to `position` after `error`, which is remembered when it came further than the others.
+    fn backtrack(&mut self, position: usize, error: ParseError) {
+        self.position = position;
+
+        if self
+            .furthest
+            .as_ref()
+            .is_none_or(|furthest| error.position > furthest.position)
+        {
+            self.furthest = Some(error);
+        }
+    }
+
+    /// `error`, unless a failure that was backtracked from came further.
+    fn explain(&mut self, error: ParseError) -> ParseError {
+        match self.furthest.take() {
+            Some(furthest) if furthest.position > error.position => furthest,
+            Some(_) | None => error,
+        }
+    }
+}
+
+/// Reads a `T` from a cursor. Functions like [`integer`] are parsers, and so are closures taking a cursor, the
+/// combinators like [`separated`] build bigger parsers out of smaller ones.
+///
+/// A parser that fails without having consumed anything can be backtracked from, by [`either`], [`optional`] and
+/// [`separated`], one that fails further in is committed and its failure is reported.
+pub trait Parser<'a, T>: Fn(&mut Cursor<'a>) -> ParseResult<T> {}
+
+impl<'a, T, F> Parser<'a, T> for F where F: Fn(&mut Cursor<'a>) -> ParseResult<T> {}
+
+/// Runs `parser` over all of `text`, line breaks at the end aside.
+///
+/// # Errors
+///
+/// When `parser` fails, or when it leaves text behind.
+pub fn parse<'a, T, P>(text: &'a str, parser: P) -> Result<T, ParseError>
+where
+    P: Parser<'a, T>,
+{
+    let mut cursor = Cursor {
+        text: text.trim_end_matches(['\r', '\n']),
+        position: 0,
+        furthest: None,
+    };
+
+    let value = parser(&mut cursor).map_err(|error| cursor.explain(error))?;
+
+    if cursor.is_at_end() {
+        Ok(value)
+    } else {
+        let error = cursor.error("the end of the input");
+
+        Err(cursor.explain(error))
+    }
+}
+
+/// A whole number, with an optional `+` or `-` in front.
+///
+/// # Errors
+///
+/// When there are no digits, or when the number doesn't fit in a `T`.
+pub fn integer<T>(cursor: &mut Cursor<'_>) -> ParseResult<T>
+where
+    T: FromStr,
+{
+    let rest = cursor.rest();
+    let unsigned = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    let digits = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+
+    if digits == 0 {
+        return Err(cursor.error("an integer"));
+    }
+
+    let number = rest
+        .get(..rest.len() - unsigned.len() + digits)
+        .unwrap_or_default();
+
+    let value = number.parse().map_err(|_| {
+        cursor.error(format!(
+            "an integer that fits in {}",
+            std::any::type_name::<T>()
+        ))
+    })?;
+
+    cursor.advance(number.len());
+
+    Ok(value)
+}
+
+/// Letters, digits and underscores.
+///
+/// # Errors
+///
+/// When there aren't any.
+pub fn identifier<'a>(cursor: &mut Cursor<'a>) -> ParseResult<&'a str> {
+    let identifier = cursor.advance_while(|c| c.is_alphanumeric() || c == '_');
+
+    if identifier.is_empty() {
+        Err(cursor.error("an identifier"))
+    } else {
+        Ok(identifier)
+    }
+}
+
+/// Anything up to the next whitespace.
+///
+/// # Errors
+///
+/// When the cursor is at whitespace or at the end.
+pub fn word<'a>(cursor: &mut Cursor<'a>) -> ParseResult<&'a str> {
+    let word = cursor.advance_while(|c| !c.is_whitespace());
+
+    if word.is_empty() {
+        Err(cursor.error("a word"))
+    } else {
+        Ok(word)
+    }
+}
+
+/// A single character that isn't whitespace.
+///
+/// # Errors
+///
+/// When the cursor is at whitespace or at the end.
+pub fn character(cursor: &mut Cursor<'_>) -> ParseResult<char> {
+    let found = cursor
+        .rest()
+        .chars()
+        .next()
+        .filter(|c| !c.is_whitespace())
+        .ok_or_else(|| cursor.error("a character"))?;
+
+    cursor.advance(found.len_utf8());
+
+    Ok(found)
+}
+
+/// A line break, `\n` or `\r\n`.
+///
+/// # Errors
+///
+/// When the line goes on.
+pub fn newline<'a>(cursor: &mut Cursor<'a>) -> ParseResult<&'a str> {
+    let rest = cursor.rest();
+
+    if rest.starts_with("\r\n") {
+        Ok(cursor.advance(2))
+    } else if rest.starts_with('\n') {
+        Ok(cursor.advance(1))
+    } else {
+        Err(cursor.error("a line break"))
+    }
+}
+
+/// Exactly `expected`.
+#[must_use]
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |cursor: &mut Cursor<'a>| {
+        if cursor.rest().starts_with(expected) {
+            Ok(cursor.advance(expected.len()))
+        } else {
+            Err(cursor.error(format!("`{}`", expected)))
+        }
+    }
+}
+
+/// The first of `options` the text starts with, so a longer option has to come before its prefixes.
+#[must_use]
+pub fn one_of<'a>(options: &'static [&'static str]) -> impl Parser<'a, &'a str> {
+    move |cursor: &mut Cursor<'a>| {
+        let rest = cursor.rest();
+
+        if let Some(option) = options.iter().find(|&&option| rest.starts_with(option)) {
+            return Ok(cursor.advance(option.len()));
+        }
+
+        let quoted: Vec<String> = options
+            .iter()
+            .map(|option| format!("`{}`", option))
+            .collect();
+
+        let expected = match quoted.split_last() {
+            Some((last, rest)) if !rest.is_empty() => format!("{} or {}", rest.join(", "), last),
+            Some((last, _)) => last.clone(),
+            None => "nothing".to_owned(),
+        };
+
+        Err(cursor.error(expected))
+    }
+}
+
+/// `parser`, with `f` applied to what it reads.
+#[must_use]
+pub fn map<'a, T, U, P, F>(parser: P, f: F) -> impl Parser<'a, U>
+where
+    P: Parser<'a, T>,
+    F: Fn(T) -> U,
+{
+    move |cursor: &mut Cursor<'a>| parser(cursor).map(&f)
+}
+
+/// `parser`, with `f` turning what it reads into a `U`. When `f` can't, it returns what was expected instead, and the
+/// failure is reported where `parser` started.
+#[must_use]
+pub fn try_map<'a, T, U, P, F>(parser: P, f: F) -> impl Parser<'a, U>
+where
+    P: Parser<'a, T>,
+    F: Fn(T) -> Result<U, String>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let start = cursor.position;
+
+        f(parser(cursor)?).map_err(|expected| {
+            cursor.position = start;
+
+            cursor.error(expected)
+        })
+    }
+}
+
+/// `first`, or `second` when `first` fails without consuming anything.
+#[must_use]
+pub fn either<'a, T, P, Q>(first: P, second: Q) -> impl Parser<'a, T>
+where
+    P: Parser<'a, T>,
+    Q: Parser<'a, T>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let start = cursor.position;
+
+        match first(cursor) {
+            Ok(value) => Ok(value),
+            Err(first_error) if first_error.position == start => {
+                cursor.position = start;
+
+                second(cursor).map_err(|second_error| {
+                    if second_error.position == start {
+                        ParseError {
+                            expected: format!(
+                                "{} or {}",
+                                first_error.expected, second_error.expected
+                            ),
+                            ..second_error
+                        }
+                    } else {
+                        second_error
+                    }
+                })
+            },
+            Err(first_error) => Err(first_error),
+        }
+    }
+}
+
+/// `parser` if it's there, nothing when it fails without consuming anything.
+#[must_use]
+pub fn optional<'a, T, P>(parser: P) -> impl Parser<'a, Option<T>>
+where
+    P: Parser<'a, T>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let start = cursor.position;
+
+        match parser(cursor) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if error.position == start => {
+                cursor.backtrack(start, error);
+
+                Ok(None)
+            },
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// One or more `item`s with a `separator` between each two. The list ends before a separator that isn't followed by
+/// an item.
+#[must_use]
+pub fn separated<'a, T, S, P, Q>(item: P, separator: Q) -> impl Parser<'a, Vec<T>>
+where
+    P: Parser<'a, T>,
+    Q: Parser<'a, S>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let mut items = vec![item(cursor)?];
+
+        loop {
+            let before_separator = cursor.position;
+
+            if let Err(error) = separator(cursor) {
+                cursor.backtrack(before_separator, error);
+
+                return Ok(items);
+            }
+
+            let before_item = cursor.position;
+
+            match item(cursor) {
+                Ok(value) => items.push(value),
+                Err(error) if error.position == before_item => {
+                    cursor.backtrack(before_separator, error);
+
+                    return Ok(items);
+                },
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+/// `item` on every line.
+#[must_use]
+pub fn lines<'a, T, P>(item: P) -> impl Parser<'a, Vec<T>>
+where
+    P: Parser<'a, T>,
+{
+    separated(item, newline)
+}
+
+/// `item` on every group of lines, the groups being separated by blank lines.
+#[must_use]
+pub fn groups<'a, T, P>(item: P) -> impl Parser<'a, Vec<T>>
+where
+    P: Parser<'a, T>,
+{
+    separated(item, |cursor: &mut Cursor<'a>| {
+        newline(cursor)?;
+        newline(cursor)?;
+
+        while newline(cursor).is_ok() {}
+
+        Ok(())
+    })
+}
+
+/// A `key` and a `value` with a `separator` in between, like `ecl:brn`.
+#[must_use]
+pub fn key_value<'a, K, S, V, P, Q, R>(key: P, separator: Q, value: R) -> impl Parser<'a, (K, V)>
+where
+    P: Parser<'a, K>,
+    Q: Parser<'a, S>,
+    R: Parser<'a, V>,
+{
+    move |cursor: &mut Cursor<'a>| {
+        let key = key(cursor)?;
+        separator(cursor)?;
+        let value = value(cursor)?;
+
+        Ok((key, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::parser::{
+        Cursor, ParseResult, either, groups, identifier, integer, key_value, lines, literal, map,
+        one_of, optional, parse, separated, try_map, word,
+    };
+
+    #[test]
+    fn primitives() {
+        assert_eq!(parse("-42", integer::<i32>), Ok(-42));
+        assert_eq!(parse("+7\n\n", integer::<u8>), Ok(7));
+        assert_eq!(parse("shiny_gold", identifier), Ok("shiny_gold"));
+        assert_eq!(parse("#123abc", word), Ok("#123abc"));
+        assert_eq!(parse("jmp", one_of(&["acc", "jmp"])), Ok("jmp"));
+    }
+
+    #[test]
+    fn combinators() {
+        let field = key_value(identifier, literal(":"), word);
+        let passports = parse(
+            "ecl:gry pid:8\nbyr:1937\n\n\nhcl:#cfa07d",
+            groups(separated(field, either(literal(" "), literal("\n")))),
+        );
+
+        assert_eq!(
+            passports,
+            Ok(vec![
+                vec![("ecl", "gry"), ("pid", "8"), ("byr", "1937")],
+                vec![("hcl", "#cfa07d")]
+            ])
+        );
+
+        let signed = |cursor: &mut Cursor<'_>| -> ParseResult<i64> {
+            let negative = optional(literal("-"))(cursor)?.is_some();
+            let number = integer::<i64>(cursor)?;
+
+            Ok(if negative { -number } else { number })
+        };
+
+        assert_eq!(
+            parse("1, -2, 3", separated(signed, literal(", "))),
+            Ok(vec![1, -2, 3])
+        );
+        assert_eq!(
+            parse("no\n3", lines(either(map(literal("no"), |_| 0), integer))),
+            Ok(vec![0, 3])
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error =
+            parse("1-3 a\n4-5", lines(separated(integer::<u32>, literal("-")))).unwrap_err();
+
+        assert_eq!((error.line(), error.column()), (1, 4));
+        assert_eq!(
+            error.to_string(),
+            "Line 1, column 4: expected the end of the input, found ` a`"
+        );
+
+        let instruction = key_value(one_of(&["acc", "jmp", "nop"]), literal(" "), integer::<i32>);
+
+        assert_eq!(
+            parse("nop +0\nmul +3", lines(instruction))
+                .unwrap_err()
+                .to_string(),
+            "Line 2, column 1: expected `acc`, `jmp` or `nop`, found `mul +3`"
+        );
+        assert_eq!(
+            parse("300", integer::<u8>).unwrap_err().to_string(),
+            "Line 1, column 1: expected an integer that fits in u8, found `300`"
+        );
+
+        let even = try_map(integer::<u32>, |number| {
+            if number % 2 == 0 {
+                Ok(number)
+            } else {
+                Err("an even number".to_owned())
+            }
+        });
+
+        let error = parse("2,4,5", separated(even, literal(","))).unwrap_err();
+
+        assert_eq!(error.snippet(), "5");
+        assert_eq!(
+            error.to_string(),
+            "Line 1, column 5: expected an even number, found `5`"
+        );
+    }
+}